@@ -4,4 +4,16 @@ use anchor_lang::prelude::*;
 pub const SEED: &str = "anchor";
 
 pub const MAXIMUM_AGE: u64 = 60; 
-pub const BTC_FEED_ID: &str = "0xe62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43"; 
+pub const BTC_FEED_ID: &str = "0xe62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43";
+
+// Share of pool funds that may back worst-case payouts, in basis points
+pub const DEFAULT_MAX_UTILIZATION_BPS: u16 = 8_000;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+pub const DEFAULT_POSITION_DURATION: i64 = 24 * 60 * 60;
+// Delay between queueing a sensitive config change and executing it
+pub const DEFAULT_TIMELOCK_DELAY: i64 = 24 * 60 * 60;
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+
+// Most positions one ladder instruction may open or settle
+pub const MAX_LADDER_RUNGS: usize = 8;
@@ -40,6 +40,36 @@ pub enum ErrorCode {
     #[msg("Please Verify price update!")]
     UnverifiedPriceUpdate,
 
+    #[msg("Position is tokenized, claim it with the position token")]
+    PositionTokenized,
+
+    #[msg("Position has already been tokenized")]
+    PositionAlreadyTokenized,
+
+    #[msg("Tokenizing a position needs its mint, token account and token program")]
+    MissingPositionTokenAccounts,
+
+    #[msg("Position token accounts are only passed when tokenizing")]
+    UnexpectedPositionTokenAccounts,
+
+    #[msg("Signer does not hold the position token")]
+    NotPositionHolder,
+
+    #[msg("Ladder must have between one and the maximum number of rungs, each with its position account")]
+    InvalidLadder,
+
+    #[msg("Invalid roll configuration")]
+    InvalidRollConfig,
+
+    #[msg("Position has no rolls remaining")]
+    NoRollsRemaining,
+
+    #[msg("Position has neither expired nor left its band")]
+    PositionNotDue,
+
+    #[msg("Position index page does not cover this position id")]
+    InvalidPositionIndex,
+
     //    <-----------------Pool------------->
 
     #[msg("Insufficient balance in trading pool")]
@@ -51,4 +81,149 @@ pub enum ErrorCode {
       #[msg("Insufficient balance in trading vault")]
     InsufficientVaultBalance,
 
+    #[msg("Pool liquidity cannot cover the position's maximum payout")]
+    InsufficientPoolLiquidity,
+
+    #[msg("Position would exceed the pool utilization cap")]
+    UtilizationCapExceeded,
+
+    #[msg("Invalid pool configuration")]
+    InvalidPoolConfig,
+
+    #[msg("Pool surplus belongs to LP shareholders")]
+    LpSharesOutstanding,
+
+    #[msg("Position exceeds the market's maximum position size")]
+    PositionTooLarge,
+
+    //    <-----------------Risk------------->
+
+    #[msg("Position would exceed the net exposure limit of a price bucket")]
+    ExposureLimitExceeded,
+
+    #[msg("Invalid exposure book configuration")]
+    InvalidExposureConfig,
+
+    #[msg("User has reached the maximum number of open positions")]
+    UserPositionLimitExceeded,
+
+    #[msg("Position would exceed the user's maximum open notional")]
+    UserNotionalLimitExceeded,
+
+    #[msg("Position would exceed the user's maximum worst-case payout")]
+    UserPayoutLimitExceeded,
+
+    //    <-----------------Fees------------->
+
+    #[msg("Fee exceeds the maximum allowed")]
+    FeeTooHigh,
+
+    #[msg("Insufficient balance in treasury")]
+    InsufficientTreasuryBalance,
+
+    #[msg("Users cannot refer themselves")]
+    SelfReferral,
+
+    #[msg("No referral rewards to claim")]
+    NoReferralRewards,
+
+    //    <-----------------Access Control------------->
+
+    #[msg("Role cannot be granted or revoked")]
+    InvalidRole,
+
+    #[msg("Member already holds this role")]
+    RoleAlreadyGranted,
+
+    #[msg("Member does not hold this role")]
+    RoleNotGranted,
+
+    #[msg("Role has reached its maximum number of members")]
+    TooManyRoleMembers,
+
+    #[msg("No authority transfer is pending for this signer")]
+    NoPendingAuthority,
+
+    #[msg("Invalid config change")]
+    InvalidConfigChange,
+
+    #[msg("Config change is still timelocked")]
+    TimelockNotExpired,
+
+    //    <-----------------Order Book------------->
+
+    #[msg("Order expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("Order is not open")]
+    OrderNotOpen,
+
+    #[msg("Order has expired")]
+    OrderExpired,
+
+    #[msg("Fill exceeds the order's remaining size")]
+    OrderOverfilled,
+
+    #[msg("Orders must be opposite sides of the same band from different owners")]
+    OrdersNotMatchable,
+
+    #[msg("Payout has already been claimed")]
+    AlreadyClaimed,
+
+    //    <-----------------Quotes------------->
+
+    #[msg("Quote is invalid")]
+    InvalidQuote,
+
+    #[msg("Quote signature is missing or does not match the maker and quote")]
+    InvalidQuoteSignature,
+
+    #[msg("Quote has expired")]
+    QuoteExpired,
+
+    #[msg("Quote maker is not whitelisted")]
+    MakerNotWhitelisted,
+
+    //    <-----------------Parimutuel------------->
+
+    #[msg("Invalid parimutuel market configuration")]
+    InvalidEpochConfig,
+
+    #[msg("Epoch index does not match the current epoch")]
+    InvalidEpoch,
+
+    #[msg("Epoch is no longer accepting stakes")]
+    StakingWindowClosed,
+
+    #[msg("Epoch has not been settled")]
+    EpochNotSettled,
+
+    #[msg("Price has already left the epoch band")]
+    EpochBandBroken,
+
+    #[msg("Price update was not published during the epoch")]
+    PriceOutsideEpoch,
+
+    //    <-----------------Migrations------------->
+
+    #[msg("Account is already at the current layout version")]
+    AccountAlreadyMigrated,
+
+    #[msg("Account does not match a known layout")]
+    InvalidAccountLayout,
+
+    //    <-----------------Compression------------->
+
+    #[msg("Position tree does not belong to this trading pool")]
+    InvalidPositionTree,
+
+    #[msg("Position tree has no free leaves")]
+    PositionTreeFull,
+
+    #[msg("Merkle proof does not match the position tree")]
+    InvalidMerkleProof,
+
+    #[msg("Merkle root is too old to be applied")]
+    StaleMerkleRoot,
+
 }
\ No newline at end of file
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, QueuedChange};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"queued_change".as_ref(),
+            protocol_config.key().as_ref(),
+            &queued_change.change_id.to_le_bytes()
+        ],
+        bump = queued_change.bump,
+        has_one = proposer,
+        close = proposer,
+    )]
+    pub queued_change: Account<'info, QueuedChange>,
+
+    /// CHECK: Receives the queued change rent, validated by has_one
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
+}
+
+impl<'info> CancelConfigChange<'info> {
+    pub fn cancel_config_change(&mut self) -> Result<()> {
+        emit!(ConfigChangeCancelledEvent {
+            change_id: self.queued_change.change_id,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ConfigChangeCancelledEvent {
+    pub change_id: u64,
+}
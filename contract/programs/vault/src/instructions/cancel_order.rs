@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{Order, OrderBook, OrderStatus, VaultState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", order_book.trading_pool.as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump = order_book.vault_bump
+    )]
+    pub order_book_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            order_book.key().as_ref(),
+            &order.order_id.to_le_bytes()
+        ],
+        bump = order.bump,
+        constraint = order.owner == user.key() @ ErrorCode::UnauthorizedAccess,
+        close = user,
+    )]
+    pub order: Account<'info, Order>,
+
+    // Unfilled collateral is returned to the user's vault
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Account<'info, VaultState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelOrder<'info> {
+    pub fn cancel_order(&mut self) -> Result<()> {
+        let refund = self.order.remaining_amount();
+
+        if self.order.status == OrderStatus::Open {
+            self.order_book.close_order()?;
+        }
+
+        if refund > 0 {
+            let order_book_key = self.order_book.key();
+            let vault_seeds = &[
+                b"order_book_vault".as_ref(),
+                order_book_key.as_ref(),
+                &[self.order_book.vault_bump],
+            ];
+            let signer_seeds = &[&vault_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.order_book_vault.to_account_info(),
+                    to: self.user_vault.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, refund)?;
+        }
+
+        emit!(OrderCancelledEvent {
+            order: self.order.key(),
+            order_id: self.order.order_id,
+            owner: self.user.key(),
+            refund,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct OrderCancelledEvent {
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub refund: u64,
+}
@@ -1,12 +1,16 @@
 use anchor_lang::prelude::*;
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2, VerificationLevel};
-use crate::state::{PositionState, PositionStatus};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{
+    ExposureBook, PositionState, PositionStatus, ProtocolConfig, Role, Roles, TradingPool, UserStats,
+};
 use crate::error::ErrorCode;
-use crate::constants::{BTC_FEED_ID,MAXIMUM_AGE};
+use crate::constants::MAXIMUM_AGE;
 
 #[derive(Accounts)]
-#[instruction(order_id: u64)]
 pub struct CheckPosition<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
     /// CHECK: Only used for seed and validation
     pub user: AccountInfo<'info>,
 
@@ -15,25 +19,64 @@ pub struct CheckPosition<'info> {
         seeds = [
             b"position".as_ref(),
             user.key().as_ref(),
-            &order_id.to_le_bytes()
+            &position.load()?.position_id.to_le_bytes()
         ],
-        bump = position.bump,
-        constraint = position.user == user.key() && position.order_id == order_id
+        bump = position.load()?.bump,
+        constraint = position.load()?.user == user.key()
+    )]
+    pub position: AccountLoader<'info, PositionState>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
     )]
-    pub position: Account<'info, PositionState>,
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
 
     #[account(
         owner = pyth_solana_receiver_sdk::ID,
         constraint = price_update.verification_level == VerificationLevel::Full,
     )]
-    pub price_update: Account<'info, PriceUpdateV2>
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    // Position owner's stats, which record the outcome
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    pub system_program: Program<'info, System>,
 }
 
 impl<'info> CheckPosition<'info> {
-    pub fn check_position(&mut self, _bumps: &CheckPositionBumps) -> Result<()> {
-        let position = &mut self.position;
+    pub fn check_position(&mut self, bumps: &CheckPositionBumps) -> Result<()> {
+        let mut position = self.position.load_mut()?;
 
-        if position.status != PositionStatus::Active {
+        if position.status() != PositionStatus::Active {
             return Ok(());
         }
 
@@ -47,23 +90,23 @@ impl<'info> CheckPosition<'info> {
         let price_data = self.price_update.get_price_no_older_than(
             &clock,
             MAXIMUM_AGE,
-            &get_feed_id_from_hex(BTC_FEED_ID)?,
+            &self.protocol_config.price_feed_id,
         ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
 
         let current_price = price_data.price as u64;
         let current_time = clock.unix_timestamp;
-        
-        let is_expired = position.is_expired(current_time);
-        let is_outside_range = position.is_outside_range(current_price);
-        let should_settle = is_expired || is_outside_range;
-
-        if should_settle {
-            let payout_percentage = position.calculate_payout(current_time, current_price);
-
-            position.settle(current_time, current_price, payout_percentage)?;
 
+        self.user_stats.ensure_initialized(position.user, bumps.user_stats);
+        if let Some(payout_percentage) = settle_pool_position(
+            &mut position,
+            &mut self.trading_pool,
+            &mut self.exposure_book,
+            &mut self.user_stats,
+            current_time,
+            current_price,
+        )? {
             emit!(PositionSettledEvent {
-                position: position.key(),
+                position: self.position.key(),
                 user: position.user,
                 settlement_time: current_time,
                 settlement_price: current_price,
@@ -76,6 +119,46 @@ impl<'info> CheckPosition<'info> {
     }
 }
 
+// Settle a pool position once the price leaves its band or it expires, moving its
+// pool liability and exposure to the final payout and recording the outcome in the
+// owner's stats. Returns the payout percentage
+pub fn settle_pool_position(
+    position: &mut PositionState,
+    trading_pool: &mut TradingPool,
+    exposure_book: &mut ExposureBook,
+    user_stats: &mut UserStats,
+    current_time: i64,
+    current_price: u64,
+) -> Result<Option<u8>> {
+    let is_expired = position.is_expired(current_time);
+    let is_outside_range = position.is_outside_range(current_price);
+
+    if !is_expired && !is_outside_range {
+        return Ok(None);
+    }
+
+    let payout_percentage = position.calculate_payout(current_time, current_price);
+
+    position.settle(current_time, current_price, payout_percentage)?;
+
+    trading_pool.settle_liability(
+        position.amount,
+        position.max_payout(),
+        position.payout_amount(payout_percentage),
+    )?;
+
+    exposure_book.remove_exposure(
+        position.position_type(),
+        position.lower_bound,
+        position.upper_bound,
+        position.amount,
+    );
+
+    user_stats.record_settlement(position.position_type(), position.amount, payout_percentage > 100)?;
+
+    Ok(Some(payout_percentage))
+}
+
 #[event]
 pub struct PositionSettledEvent {
     pub position: Pubkey,
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{
+    ExposureBook, PositionState, PositionStatus, ProtocolConfig, Role, Roles, TradingPool, UserStats,
+};
+use crate::instructions::{settle_pool_position, PositionSettledEvent};
+use crate::error::ErrorCode;
+use crate::constants::{MAXIMUM_AGE, MAX_LADDER_RUNGS};
+
+// The ladder's position accounts are passed as writable remaining accounts, and
+// must all be rungs of the given user's ladder
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct CheckPositionLadder<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    // Ladder owner's stats, which record each rung's outcome
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CheckPositionLadder<'info> {
+    pub fn check_position_ladder(
+        &mut self,
+        user: Pubkey,
+        ladder_id: u64,
+        position_accounts: &'info [AccountInfo<'info>],
+        bumps: &CheckPositionLadderBumps,
+    ) -> Result<()> {
+        require!(
+            !position_accounts.is_empty() && position_accounts.len() <= MAX_LADDER_RUNGS,
+            ErrorCode::InvalidLadder
+        );
+
+        let clock = Clock::get()?;
+
+        let price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+
+        let current_price = price_data.price as u64;
+        let current_time = clock.unix_timestamp;
+
+        self.user_stats.ensure_initialized(user, bumps.user_stats);
+
+        // Every rung is settled against the same price
+        for position_info in position_accounts.iter() {
+            let loader = AccountLoader::<PositionState>::try_from(position_info)?;
+            let mut position = loader.load_mut()?;
+            require!(
+                position.user == user && position.ladder_id() == Some(ladder_id),
+                ErrorCode::InvalidLadder
+            );
+
+            if position.status() != PositionStatus::Active {
+                continue;
+            }
+
+            if let Some(payout_percentage) = settle_pool_position(
+                &mut position,
+                &mut self.trading_pool,
+                &mut self.exposure_book,
+                &mut self.user_stats,
+                current_time,
+                current_price,
+            )? {
+                emit!(PositionSettledEvent {
+                    position: loader.key(),
+                    user: position.user,
+                    settlement_time: current_time,
+                    settlement_price: current_price,
+                    payout_percentage,
+                    is_winner: payout_percentage > 100,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use crate::state::{CompressedPosition, PositionStatus, PositionTree, ProtocolConfig, TradingPool, UserStats, VaultState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ClaimCompressedPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = position_tree.load()?.trading_pool == trading_pool.key() @ ErrorCode::InvalidPositionTree,
+    )]
+    pub position_tree: AccountLoader<'info, PositionTree>,
+
+    // User's personal vault where funds will be transferred to
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the winnings fee
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // User's running position stats
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimCompressedPosition<'info> {
+    // `position` is the current leaf, proven against `root` by `proof`
+    pub fn claim_compressed_position(
+        &mut self,
+        root: [u8; 32],
+        position: CompressedPosition,
+        proof: Vec<[u8; 32]>,
+        bumps: &ClaimCompressedPositionBumps,
+    ) -> Result<()> {
+        require!(position.user == self.user.key(), ErrorCode::UnauthorizedAccess);
+        require!(position.status == PositionStatus::Settled, ErrorCode::PositionNotSettled);
+
+        let mut position_state = position.to_position_state()?;
+        let settlement_data = position_state
+            .settlement_data()
+            .ok_or(ErrorCode::PositionNotSettled)?;
+
+        let payout_amount = position_state.payout_amount(settlement_data.payout_percentage);
+
+        // Winnings fee is taken out of the profit, the rest goes to the user
+        let winnings_fee = self.protocol_config.winnings_fee(position.amount, payout_amount);
+        let user_payout = payout_amount.checked_sub(winnings_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        self.trading_pool.record_claim(position.amount, payout_amount)?;
+        self.user_vault_state.record_close(position.amount, position_state.max_payout());
+
+        position_state.claim()?;
+        let mut claimed = position;
+        claimed.update_from(&position_state);
+
+        // Marking the leaf claimed is what stops it being claimed twice
+        let mut position_tree = self.position_tree.load_mut()?;
+        position_tree.replace_leaf(root, &proof, position.leaf_index, position.leaf()?, claimed.leaf()?)?;
+
+        self.user_stats.ensure_initialized(position.user, bumps.user_stats);
+        self.user_stats.record_claim(position.amount, user_payout, winnings_fee)?;
+
+        if payout_amount > 0 {
+            require!(
+                self.trading_pool_vault.lamports() >= payout_amount,
+                ErrorCode::InsufficientPoolBalance
+            );
+
+            let pool_vault_seeds = &[
+                b"trading_pool_vault",
+                self.trading_pool.to_account_info().key.as_ref(),
+                &[self.trading_pool.vault_bump],
+            ];
+            let signer_seeds = &[&pool_vault_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.trading_pool_vault.to_account_info(),
+                    to: self.user_vault.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, user_payout)?;
+
+            if winnings_fee > 0 {
+                let cpi_ctx = CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.trading_pool_vault.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+
+                transfer(cpi_ctx, winnings_fee)?;
+                self.protocol_config.record_fee(winnings_fee)?;
+            }
+        }
+
+        emit!(CompressedPositionClaimedEvent {
+            position_tree: self.position_tree.key(),
+            position: claimed,
+            root: position_tree.root(),
+            payout_amount: user_payout,
+            winnings_fee,
+            trading_pool: self.trading_pool.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct CompressedPositionClaimedEvent {
+    pub position_tree: Pubkey,
+    pub position: CompressedPosition,
+    pub root: [u8; 32],
+    pub payout_amount: u64,
+    pub winnings_fee: u64,
+    pub trading_pool: Pubkey,
+}
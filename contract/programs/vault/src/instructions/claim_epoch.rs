@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{Epoch, EpochStake, ParimutuelMarket, VaultState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ClaimEpoch<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"parimutuel_market", market.trading_pool.as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, ParimutuelMarket>,
+
+    #[account(
+        seeds = [b"epoch", market.key().as_ref(), &epoch.epoch_index.to_le_bytes()],
+        bump = epoch.bump,
+    )]
+    pub epoch: Box<Account<'info, Epoch>>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch_vault", epoch.key().as_ref()],
+        bump = epoch.vault_bump
+    )]
+    pub epoch_vault: SystemAccount<'info>,
+
+    // Closed on claim, so each stake pays out once
+    #[account(
+        mut,
+        seeds = [b"epoch_stake", epoch.key().as_ref(), user.key().as_ref()],
+        bump = epoch_stake.bump,
+        constraint = epoch_stake.user == user.key() @ ErrorCode::UnauthorizedAccess,
+        close = user,
+    )]
+    pub epoch_stake: Account<'info, EpochStake>,
+
+    // User's personal vault where the payout is sent
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Account<'info, VaultState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimEpoch<'info> {
+    pub fn claim_epoch(&mut self) -> Result<()> {
+        let payout_amount = self.epoch.payout_for(&self.epoch_stake)?;
+
+        if payout_amount > 0 {
+            let epoch_key = self.epoch.key();
+            let epoch_vault_seeds = &[
+                b"epoch_vault".as_ref(),
+                epoch_key.as_ref(),
+                &[self.epoch.vault_bump],
+            ];
+            let signer_seeds = &[&epoch_vault_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.epoch_vault.to_account_info(),
+                    to: self.user_vault.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, payout_amount)?;
+        }
+
+        emit!(EpochClaimedEvent {
+            epoch: self.epoch.key(),
+            user: self.user.key(),
+            payout_amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct EpochClaimedEvent {
+    pub epoch: Pubkey,
+    pub user: Pubkey,
+    pub payout_amount: u64,
+}
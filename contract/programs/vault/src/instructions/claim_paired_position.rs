@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{OrderBook, PairedPosition, PositionStatus, PositionType, ProtocolConfig, VaultState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ClaimPairedPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"order_book", order_book.trading_pool.as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"paired_position".as_ref(),
+            order_book.key().as_ref(),
+            &paired_position.match_id.to_le_bytes()
+        ],
+        bump = paired_position.bump,
+    )]
+    pub paired_position: Account<'info, PairedPosition>,
+
+    #[account(
+        mut,
+        seeds = [b"paired_escrow", paired_position.key().as_ref()],
+        bump = paired_position.escrow_bump
+    )]
+    pub paired_escrow: SystemAccount<'info>,
+
+    // User's personal vault where the payout is sent
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the winnings fee
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Receives the escrow rent on the last claim, checked against the paired position
+    #[account(
+        mut,
+        address = paired_position.rent_payer @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimPairedPosition<'info> {
+    pub fn claim_paired_position(&mut self) -> Result<()> {
+        let side = self.paired_position
+            .side_of(&self.user.key())
+            .ok_or(ErrorCode::UnauthorizedAccess)?;
+
+        let payout_amount = self.paired_position.claim(side)?;
+
+        // Winnings fee is taken out of the profit over the side's own stake
+        let winnings_fee = self.protocol_config.winnings_fee(self.paired_position.stake_of(side), payout_amount);
+        let user_payout = payout_amount.checked_sub(winnings_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        if payout_amount > 0 {
+            let paired_position_key = self.paired_position.key();
+            let escrow_seeds = &[
+                b"paired_escrow".as_ref(),
+                paired_position_key.as_ref(),
+                &[self.paired_position.escrow_bump],
+            ];
+            let signer_seeds = &[&escrow_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.paired_escrow.to_account_info(),
+                    to: self.user_vault.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, user_payout)?;
+
+            if winnings_fee > 0 {
+                let cpi_ctx = CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.paired_escrow.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+
+                transfer(cpi_ctx, winnings_fee)?;
+                self.protocol_config.record_fee(winnings_fee)?;
+            }
+        }
+
+        if self.paired_position.status == PositionStatus::Claimed {
+            self.paired_position.refund_escrow_rent(
+                self.paired_position.key(),
+                &self.paired_escrow.to_account_info(),
+                &self.rent_payer.to_account_info(),
+                &self.system_program.to_account_info(),
+            )?;
+        }
+
+        emit!(PairedPositionClaimedEvent {
+            paired_position: self.paired_position.key(),
+            user: self.user.key(),
+            position_type: side,
+            payout_amount: user_payout,
+            winnings_fee,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PairedPositionClaimedEvent {
+    pub paired_position: Pubkey,
+    pub user: Pubkey,
+    pub position_type: PositionType,
+    pub payout_amount: u64,
+    pub winnings_fee: u64,
+}
@@ -1,11 +1,10 @@
 // Updated claim_position.rs
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{Transfer, transfer};
-use crate::state::{PositionState, PositionStatus, VaultState, TradingPool};
+use crate::state::{remove_from_index, PositionIndex, PositionState, PositionStatus, ProtocolConfig, VaultState, TradingPool, UserStats};
 use crate::error::ErrorCode;
 
 #[derive(Accounts)]
-#[instruction(order_id: u64)]
 pub struct ClaimPosition<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -15,14 +14,14 @@ pub struct ClaimPosition<'info> {
         seeds = [
             b"position".as_ref(),
             user.key().as_ref(),
-            &order_id.to_le_bytes()
+            &position.load()?.position_id.to_le_bytes()
         ],
-        bump = position.bump,
-        constraint = position.user == user.key(),
-        constraint = position.order_id == order_id,
-        constraint = position.status == PositionStatus::Settled @ ErrorCode::PositionNotSettled,
+        bump = position.load()?.bump,
+        constraint = position.load()?.user == user.key(),
+        constraint = position.load()?.position_mint().is_none() @ ErrorCode::PositionTokenized,
+        constraint = position.load()?.status() == PositionStatus::Settled @ ErrorCode::PositionNotSettled,
     )]
-    pub position: Account<'info, PositionState>,
+    pub position: AccountLoader<'info, PositionState>,
 
     // User's personal vault where funds will be transferred to
     #[account(
@@ -33,6 +32,7 @@ pub struct ClaimPosition<'info> {
     pub user_vault: SystemAccount<'info>,
 
     #[account(
+        mut,
         seeds = [b"vault_state", user.key().as_ref()],
         bump = user_vault_state.state_bump
     )]
@@ -54,52 +54,86 @@ pub struct ClaimPosition<'info> {
     )]
     pub trading_pool_vault: SystemAccount<'info>,
 
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the winnings fee
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // User's running position stats
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    // Index page the claimed position id is removed from
+    #[account(
+        mut,
+        seeds = [
+            b"position_index".as_ref(),
+            user.key().as_ref(),
+            &PositionIndex::page_of(position.load()?.position_id).to_le_bytes()
+        ],
+        bump = position_index.bump
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> ClaimPosition<'info> {
-    pub fn claim(&mut self, _bumps: &ClaimPositionBumps) -> Result<()> {
-        let position = &mut self.position;
+    pub fn claim(&mut self, bumps: &ClaimPositionBumps) -> Result<()> {
+        let mut position = self.position.load_mut()?;
         
         // Get settlement data
         let settlement_data = position
-            .settlement_data
+            .settlement_data()
             .ok_or(ErrorCode::PositionNotSettled)?;
 
         // Calculate payout amount based on percentage
         // 100 = full refund, 200 = 2x payout, etc.
-        let payout_amount =
-            (position.amount as u128 * settlement_data.payout_percentage as u128 / 100) as u64;
-
-        // Update trading pool accounting before marking position as claimed
-        // Reduce the active amount regardless of payout
-        self.trading_pool.total_active_amount = self.trading_pool.total_active_amount
-            .checked_sub(position.amount)
-            .ok_or(ErrorCode::MathOverflow)?;
-            
-        // If payout is greater than original amount, it's a win (profit)
-        // If less, it's a loss (some funds stay in the pool)
-        if payout_amount > position.amount {
-            // Winner: reduce total pool by payout amount
-            self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount
-                .checked_sub(payout_amount)
-                .ok_or(ErrorCode::MathOverflow)?;
-        } else {
-            // Loser or break-even: reduce total pool by the payout amount
-            self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount
-                .checked_sub(payout_amount)
-                .ok_or(ErrorCode::MathOverflow)?;
-        }
-            
+        let payout_amount = position.payout_amount(settlement_data.payout_percentage);
+
+        // Winnings fee is taken out of the profit, the rest goes to the user
+        let winnings_fee = self.protocol_config.winnings_fee(position.amount, payout_amount);
+        let user_payout = payout_amount.checked_sub(winnings_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        // Update trading pool accounting before marking position as claimed.
+        // The active amount drops by the stake and the pool by the payout, so on a
+        // loss the rest of the stake stays in the pool
+        self.trading_pool.record_claim(position.amount, payout_amount)?;
+
+        // Free the position's share of the user's caps
+        self.user_vault_state.record_close(position.amount, position.max_payout());
+
         // Mark position as claimed
         position.claim()?;
 
+        remove_from_index(&mut self.position_index, position.position_id, &self.user.to_account_info())?;
+
+        self.user_stats.ensure_initialized(position.user, bumps.user_stats);
+        self.user_stats.record_claim(position.amount, user_payout, winnings_fee)?;
+
         // Skip transfer if payout is 0
         if payout_amount == 0 {
             emit!(PositionClaimedEvent {
-                position: position.key(),
+                position: self.position.key(),
                 user: position.user,
                 payout_amount: 0,
+                winnings_fee: 0,
                 trading_pool: self.trading_pool.key(),
             });
             return Ok(());
@@ -112,7 +146,7 @@ impl<'info> ClaimPosition<'info> {
             ErrorCode::InsufficientPoolBalance
         );
 
-        // Transfer payout from trading pool vault to user's vault
+        // Transfer payout net of fees from trading pool vault to user's vault
         let pool_vault_seeds = &[
             b"trading_pool_vault",
             self.trading_pool.to_account_info().key.as_ref(),
@@ -129,12 +163,28 @@ impl<'info> ClaimPosition<'info> {
             signer_seeds,
         );
 
-        transfer(cpi_ctx, payout_amount)?;
+        transfer(cpi_ctx, user_payout)?;
+
+        // Transfer winnings fee from trading pool vault to treasury
+        if winnings_fee > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.trading_pool_vault.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, winnings_fee)?;
+            self.protocol_config.record_fee(winnings_fee)?;
+        }
         
         emit!(PositionClaimedEvent {
-            position: position.key(),
+            position: self.position.key(),
             user: position.user,
-            payout_amount,
+            payout_amount: user_payout,
+            winnings_fee,
             trading_pool: self.trading_pool.key(),
         });
         
@@ -147,5 +197,6 @@ pub struct PositionClaimedEvent {
     pub position: Pubkey,
     pub user: Pubkey,
     pub payout_amount: u64,
+    pub winnings_fee: u64,
     pub trading_pool: Pubkey,
 }
\ No newline at end of file
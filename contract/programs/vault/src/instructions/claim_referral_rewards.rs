@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{ProtocolConfig, Referrer};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", authority.key().as_ref()],
+        bump = referrer.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    // Rebates are held in the treasury until claimed
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimReferralRewards<'info> {
+    pub fn claim_referral_rewards(&mut self) -> Result<()> {
+        let amount = self.referrer.claimable;
+        require!(amount > 0, ErrorCode::NoReferralRewards);
+
+        self.referrer.claimable = 0;
+        self.protocol_config.total_referral_owed = self.protocol_config.total_referral_owed
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Transfer rewards from treasury to referrer
+        let treasury_seeds = &[
+            b"treasury".as_ref(),
+            self.protocol_config.to_account_info().key.as_ref(),
+            &[self.protocol_config.treasury_bump],
+        ];
+        let signer_seeds = &[&treasury_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.treasury.to_account_info(),
+                to: self.authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        emit!(ReferralRewardsClaimedEvent {
+            referrer: self.referrer.key(),
+            authority: self.authority.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ReferralRewardsClaimedEvent {
+    pub referrer: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+use crate::state::{remove_from_index, PositionIndex, PositionState, PositionStatus, ProtocolConfig, TradingPool, VaultState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ClaimTokenizedPosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position".as_ref(),
+            position.load()?.user.as_ref(),
+            &position.load()?.position_id.to_le_bytes()
+        ],
+        bump = position.load()?.bump,
+        constraint = position.load()?.status() == PositionStatus::Settled @ ErrorCode::PositionNotSettled,
+        constraint = position.load()?.position_mint() == Some(position_mint.key()) @ ErrorCode::NotPositionHolder,
+    )]
+    pub position: AccountLoader<'info, PositionState>,
+
+    #[account(
+        mut,
+        seeds = [b"position_mint", position.key().as_ref()],
+        bump,
+    )]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    // Burned on claim, so the payout follows whoever holds it
+    #[account(
+        mut,
+        token::mint = position_mint,
+        token::authority = holder,
+        constraint = holder_position_token.amount == 1 @ ErrorCode::NotPositionHolder,
+    )]
+    pub holder_position_token: Box<Account<'info, TokenAccount>>,
+
+    // Holder's personal vault where funds will be transferred to
+    #[account(
+        mut,
+        seeds = [b"vault", holder_vault_state.key().as_ref()],
+        bump = holder_vault_state.vault_bump
+    )]
+    pub holder_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", holder.key().as_ref()],
+        bump = holder_vault_state.state_bump
+    )]
+    pub holder_vault_state: Box<Account<'info, VaultState>>,
+
+    // Vault state of the user who opened the position, whose caps it counts against
+    #[account(
+        mut,
+        seeds = [b"vault_state", position.load()?.user.as_ref()],
+        bump = owner_vault_state.state_bump
+    )]
+    pub owner_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Box<Account<'info, TradingPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the winnings fee
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // Index page of the user who opened the position, which the claimed id is removed from
+    #[account(
+        mut,
+        seeds = [
+            b"position_index".as_ref(),
+            position.load()?.user.as_ref(),
+            &PositionIndex::page_of(position.load()?.position_id).to_le_bytes()
+        ],
+        bump = position_index.bump
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+
+    /// CHECK: User who opened the position, refunded the index page rent if the page empties
+    #[account(
+        mut,
+        address = position.load()?.user @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub owner: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimTokenizedPosition<'info> {
+    pub fn claim_tokenized_position(&mut self, _bumps: &ClaimTokenizedPositionBumps) -> Result<()> {
+        let mut position = self.position.load_mut()?;
+        let settlement_data = position
+            .settlement_data()
+            .ok_or(ErrorCode::PositionNotSettled)?;
+
+        let payout_amount = position.payout_amount(settlement_data.payout_percentage);
+
+        // Winnings fee is taken out of the profit, the rest goes to the holder
+        let winnings_fee = self.protocol_config.winnings_fee(position.amount, payout_amount);
+        let holder_payout = payout_amount.checked_sub(winnings_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        // Burn the position token so the claim cannot be repeated
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.position_mint.to_account_info(),
+                from: self.holder_position_token.to_account_info(),
+                authority: self.holder.to_account_info(),
+            },
+        );
+
+        burn(cpi_ctx, 1)?;
+
+        self.trading_pool.record_claim(position.amount, payout_amount)?;
+        self.owner_vault_state.record_close(position.amount, position.max_payout());
+        position.claim()?;
+
+        remove_from_index(&mut self.position_index, position.position_id, &self.owner.to_account_info())?;
+
+        if payout_amount > 0 {
+            require!(
+                self.trading_pool_vault.lamports() >= payout_amount,
+                ErrorCode::InsufficientPoolBalance
+            );
+
+            let pool_vault_seeds = &[
+                b"trading_pool_vault",
+                self.trading_pool.to_account_info().key.as_ref(),
+                &[self.trading_pool.vault_bump],
+            ];
+            let signer_seeds = &[&pool_vault_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.trading_pool_vault.to_account_info(),
+                    to: self.holder_vault.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, holder_payout)?;
+
+            if winnings_fee > 0 {
+                let cpi_ctx = CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.trading_pool_vault.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+
+                transfer(cpi_ctx, winnings_fee)?;
+                self.protocol_config.record_fee(winnings_fee)?;
+            }
+        }
+
+        emit!(TokenizedPositionClaimedEvent {
+            position: self.position.key(),
+            holder: self.holder.key(),
+            position_mint: self.position_mint.key(),
+            payout_amount: holder_payout,
+            winnings_fee,
+            trading_pool: self.trading_pool.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct TokenizedPositionClaimedEvent {
+    pub position: Pubkey,
+    pub holder: Pubkey,
+    pub position_mint: Pubkey,
+    pub payout_amount: u64,
+    pub winnings_fee: u64,
+    pub trading_pool: Pubkey,
+}
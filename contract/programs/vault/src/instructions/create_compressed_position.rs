@@ -0,0 +1,227 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{
+    CompressedPosition, ExposureBook, PositionStatus, PositionTree, PositionType, ProtocolConfig,
+    Referrer, TradingPool, UserStats, VaultState,
+};
+use crate::error::ErrorCode;
+use crate::constants::MAXIMUM_AGE;
+
+#[derive(Accounts)]
+pub struct CreateCompressedPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // Tree the position is appended to
+    #[account(
+        mut,
+        constraint = position_tree.load()?.trading_pool == trading_pool.key() @ ErrorCode::InvalidPositionTree,
+    )]
+    pub position_tree: AccountLoader<'info, PositionTree>,
+
+    // User's personal vault
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the opening fee
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // User's running position stats
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    // Optional referrer credited with a share of the opening fee
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.authority.as_ref()],
+        bump = referrer.bump,
+        constraint = referrer.authority != user.key() @ ErrorCode::SelfReferral,
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(
+        mut,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateCompressedPosition<'info> {
+    pub fn create_compressed_position(
+        &mut self,
+        position_type: PositionType,
+        lower_bound: u64,
+        upper_bound: u64,
+        amount: u64,
+        bumps: &CreateCompressedPositionBumps,
+    ) -> Result<u64> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+        require!(lower_bound < upper_bound, ErrorCode::InvalidRange);
+
+        require!(
+            self.user_vault.lamports() >= amount,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        let clock = Clock::get()?;
+        self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+
+        // Opening fee is taken out of the amount, the rest is staked
+        let opening_fee = self.protocol_config.opening_fee(amount);
+        let stake = amount.checked_sub(opening_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        // Size limits apply to what is actually staked
+        self.trading_pool.check_position_size(stake)?;
+
+        let mut position_tree = self.position_tree.load_mut()?;
+        let position = CompressedPosition {
+            leaf_index: position_tree.next_leaf_index,
+            user: self.user.key(),
+            position_type,
+            lower_bound,
+            upper_bound,
+            start_time: clock.unix_timestamp,
+            duration: self.protocol_config.position_duration,
+            amount: stake,
+            status: PositionStatus::Active,
+            settlement_data: None,
+        };
+        position_tree.append(position.leaf()?)?;
+
+        self.user_stats.ensure_initialized(self.user.key(), bumps.user_stats);
+        self.user_stats.record_open(stake, opening_fee)?;
+
+        // Same caps, solvency and exposure checks as a position account
+        let max_payout = position.to_position_state()?.max_payout();
+        self.user_vault_state.record_open(stake, max_payout, &self.trading_pool)?;
+        self.trading_pool.check_solvency(stake, max_payout)?;
+        self.exposure_book.add_exposure(position_type, lower_bound, upper_bound, stake)?;
+
+        // Transfer funds from user vault to trading pool vault
+        let user_vault_seeds = &[
+            b"vault".as_ref(),
+            self.user_vault_state.to_account_info().key.as_ref(),
+            &[self.user_vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&user_vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.user_vault.to_account_info(),
+                to: self.trading_pool_vault.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, stake)?;
+
+        if opening_fee > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user_vault.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, opening_fee)?;
+            self.protocol_config.record_fee(opening_fee)?;
+        }
+
+        let mut referral_rebate = 0;
+        if let Some(referrer) = self.referrer.as_mut() {
+            referral_rebate = self.protocol_config.referral_rebate(opening_fee);
+            referrer.record_referral(amount, referral_rebate)?;
+            self.protocol_config.record_referral_rebate(referral_rebate)?;
+        }
+
+        self.trading_pool.total_active_amount = self.trading_pool.total_active_amount.checked_add(stake)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount.checked_add(stake)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.trading_pool.add_liability(stake, max_payout)?;
+
+        emit!(CompressedPositionCreatedEvent {
+            position_tree: self.position_tree.key(),
+            position,
+            root: position_tree.root(),
+            opening_fee,
+            referrer: self.referrer.as_ref().map(|referrer| referrer.key()),
+            referral_rebate,
+            trading_pool: self.trading_pool.key(),
+        });
+
+        Ok(position.leaf_index)
+    }
+}
+
+// Carries the full leaf, which clients keep to build proofs against later roots
+#[event]
+pub struct CompressedPositionCreatedEvent {
+    pub position_tree: Pubkey,
+    pub position: CompressedPosition,
+    pub root: [u8; 32],
+    pub opening_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_rebate: u64,
+    pub trading_pool: Pubkey,
+}
@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{OrderBook, PairedPosition, PositionStatus, ProtocolConfig, VaultState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct CreatePairedPosition<'info> {
+    #[account(mut)]
+    pub stay_in_user: Signer<'info>,
+
+    pub breakout_user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", order_book.trading_pool.as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+
+    #[account(
+        init,
+        payer = stay_in_user,
+        space = 8 + PairedPosition::INIT_SPACE,
+        seeds = [
+            b"paired_position".as_ref(),
+            order_book.key().as_ref(),
+            &order_book.next_match_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub paired_position: Box<Account<'info, PairedPosition>>,
+
+    // Holds both stakes until the paired position is claimed
+    #[account(
+        mut,
+        seeds = [b"paired_escrow", paired_position.key().as_ref()],
+        bump
+    )]
+    pub paired_escrow: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", stay_in_vault_state.key().as_ref()],
+        bump = stay_in_vault_state.vault_bump,
+    )]
+    pub stay_in_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", stay_in_user.key().as_ref()],
+        bump = stay_in_vault_state.state_bump
+    )]
+    pub stay_in_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", breakout_vault_state.key().as_ref()],
+        bump = breakout_vault_state.vault_bump,
+    )]
+    pub breakout_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", breakout_user.key().as_ref()],
+        bump = breakout_vault_state.state_bump
+    )]
+    pub breakout_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreatePairedPosition<'info> {
+    pub fn create_paired_position(
+        &mut self,
+        lower_bound: u64,
+        upper_bound: u64,
+        stake: u64,
+        bumps: &CreatePairedPositionBumps,
+    ) -> Result<()> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+        require!(lower_bound < upper_bound, ErrorCode::InvalidRange);
+        require!(stake >= VaultState::MIN_ORDER_AMOUNT, ErrorCode::AmountTooSmall);
+        require!(
+            self.stay_in_user.key() != self.breakout_user.key(),
+            ErrorCode::OrdersNotMatchable
+        );
+        require!(
+            self.stay_in_vault.lamports() >= stake && self.breakout_vault.lamports() >= stake,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        let match_id = self.order_book.take_match_id()?;
+
+        self.paired_position.set_inner(PairedPosition {
+            version: PairedPosition::VERSION,
+            match_id,
+            order_book: self.order_book.key(),
+            stay_in_user: self.stay_in_user.key(),
+            breakout_user: self.breakout_user.key(),
+            lower_bound,
+            upper_bound,
+            stay_in_stake: stake,
+            breakout_stake: stake,
+            start_time: Clock::get()?.unix_timestamp,
+            duration: self.protocol_config.position_duration,
+            status: PositionStatus::Active,
+            settlement_data: None,
+            stay_in_payout: 0,
+            breakout_payout: 0,
+            stay_in_claimed: false,
+            breakout_claimed: false,
+            bump: bumps.paired_position,
+            escrow_bump: bumps.paired_escrow,
+            rent_payer: Pubkey::default(),
+            reserved: [0; 32],
+        });
+
+        // Escrow rent is refunded on the last claim
+        self.paired_position.fund_escrow_rent(
+            &self.paired_escrow.to_account_info(),
+            &self.stay_in_user.to_account_info(),
+            &self.system_program.to_account_info(),
+        )?;
+
+        // Each side stakes from its own vault into the escrow
+        for (vault, vault_state) in [
+            (&self.stay_in_vault, &self.stay_in_vault_state),
+            (&self.breakout_vault, &self.breakout_vault_state),
+        ] {
+            let vault_seeds = &[
+                b"vault".as_ref(),
+                vault_state.to_account_info().key.as_ref(),
+                &[vault_state.vault_bump],
+            ];
+            let signer_seeds = &[&vault_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: self.paired_escrow.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, stake)?;
+        }
+
+        emit!(PairedPositionCreatedEvent {
+            paired_position: self.paired_position.key(),
+            match_id,
+            stay_in_user: self.stay_in_user.key(),
+            breakout_user: self.breakout_user.key(),
+            lower_bound,
+            upper_bound,
+            stake,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PairedPositionCreatedEvent {
+    pub paired_position: Pubkey,
+    pub match_id: u64,
+    pub stay_in_user: Pubkey,
+    pub breakout_user: Pubkey,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub stake: u64,
+}
@@ -1,19 +1,18 @@
 // Updated create_position.rs
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2, VerificationLevel};
-use crate::state::{PositionState, PositionType, TradingPool, VaultState};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{
+    ExposureBook, PositionIndex, PositionState, PositionType, ProtocolConfig, Referrer, TradingPool,
+    UserStats, VaultState,
+};
 use crate::error::ErrorCode;
-use crate::constants::{BTC_FEED_ID, MAXIMUM_AGE};
+use crate::constants::MAXIMUM_AGE;
+use crate::instructions::mint_position_token;
 
 #[derive(Accounts)]
-#[instruction(
-    position_type: PositionType,
-    lower_bound: u64,
-    upper_bound: u64,
-    order_id: u64,
-    amount: u64,
-)]
 pub struct CreatePosition<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -25,11 +24,11 @@ pub struct CreatePosition<'info> {
         seeds = [
             b"position".as_ref(),
             user.key().as_ref(),
-            &order_id.to_le_bytes()
+            &user_vault_state.next_position_id.to_le_bytes()
         ],
         bump
     )]
-    pub position: Account<'info, PositionState>,
+    pub position: AccountLoader<'info, PositionState>,
     
     // User's personal vault
     #[account(
@@ -39,7 +38,9 @@ pub struct CreatePosition<'info> {
     )]
     pub user_vault: SystemAccount<'info>,
     
+    // Assigns the position id
     #[account(
+        mut,
         seeds = [b"vault_state", user.key().as_ref()],
         bump = user_vault_state.state_bump
     )]
@@ -61,12 +62,90 @@ pub struct CreatePosition<'info> {
     )]
     pub trading_pool_vault: SystemAccount<'info>,
     
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the opening fee
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // User's running position stats
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    // Index page that lists the new position id
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + PositionIndex::INIT_SPACE,
+        seeds = [
+            b"position_index".as_ref(),
+            user.key().as_ref(),
+            &PositionIndex::page_of(user_vault_state.next_position_id).to_le_bytes()
+        ],
+        bump
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+    
+    // Optional referrer credited with a share of the opening fee
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.authority.as_ref()],
+        bump = referrer.bump,
+        constraint = referrer.authority != user.key() @ ErrorCode::SelfReferral,
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+    
+    // Aggregated exposure for this market
+    #[account(
+        mut,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+    
     // Pyth price update
     #[account(
         owner = pyth_solana_receiver_sdk::ID,
         constraint = price_update.verification_level == VerificationLevel::Full,
     )]
     pub price_update: Account<'info, PriceUpdateV2>,
+
+    // Position token, only passed when the position is tokenized at creation
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"position_mint", position.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = position,
+    )]
+    pub position_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = position_mint,
+        associated_token::authority = user,
+    )]
+    pub user_position_token: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
     
     pub system_program: Program<'info, System>,
 }
@@ -77,14 +156,16 @@ impl<'info> CreatePosition<'info> {
         position_type: PositionType,
         lower_bound: u64,
         upper_bound: u64,
-        order_id: u64,
         amount: u64,
+        tokenize: bool,
         bumps: &CreatePositionBumps
-    ) -> Result<()> {
+    ) -> Result<u64> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
         require!(lower_bound < upper_bound, ErrorCode::InvalidRange);
+        // Passed token accounts are created even if unused, taking the mint address for good
         require!(
-            amount >= VaultState::MIN_ORDER_AMOUNT,
-            ErrorCode::AmountTooSmall
+            tokenize || (self.position_mint.is_none() && self.user_position_token.is_none()),
+            ErrorCode::UnexpectedPositionTokenAccounts
         );
 
         // Check user vault balance
@@ -101,27 +182,54 @@ impl<'info> CreatePosition<'info> {
             ErrorCode::UnverifiedPriceUpdate
         );
         
-        // Validate BTC price feed - this ensures the feed ID matches BTC
+        // Validate price feed - this ensures the feed ID matches the configured market
         let _price_data = self.price_update.get_price_no_older_than(
             &clock,
             MAXIMUM_AGE,
-            &get_feed_id_from_hex(BTC_FEED_ID)?,
+            &self.protocol_config.price_feed_id,
         ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
         
         let start_time = clock.unix_timestamp;
 
+        // Opening fee is taken out of the amount, the rest is staked
+        let opening_fee = self.protocol_config.opening_fee(amount);
+        let stake = amount.checked_sub(opening_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        // Size limits apply to what is actually staked
+        self.trading_pool.check_position_size(stake)?;
+
+        // Position ids are assigned per user, in order
+        let position_id = self.user_vault_state.take_position_id()?;
+        self.position_index.ensure_initialized(self.user.key(), PositionIndex::page_of(position_id), bumps.position_index);
+        self.position_index.insert(position_id)?;
+
         // Initialize position state
-        self.position.initialize(
+        let mut position = self.position.load_init()?;
+        position.initialize(
             self.user.key(),
             position_type,
             lower_bound,
             upper_bound,
             start_time,
-            order_id,
-            amount,
+            self.protocol_config.position_duration,
+            position_id,
+            stake,
             bumps.position,
         )?;
 
+        self.user_stats.ensure_initialized(self.user.key(), bumps.user_stats);
+        self.user_stats.record_open(stake, opening_fee)?;
+
+        // Reject positions over the user's caps for this market
+        let max_payout = position.max_payout();
+        self.user_vault_state.record_open(stake, max_payout, &self.trading_pool)?;
+
+        // Reject positions the pool could not pay out in the worst case
+        self.trading_pool.check_solvency(stake, max_payout)?;
+
+        // Reject positions that concentrate too much risk in one price bucket
+        self.exposure_book.add_exposure(position_type, lower_bound, upper_bound, stake)?;
+
         // Transfer funds from user vault to trading pool vault
         let user_vault_seeds = &[
             b"vault".as_ref(),
@@ -139,27 +247,69 @@ impl<'info> CreatePosition<'info> {
             signer_seeds,
         );
         
-        transfer(cpi_ctx, amount)?;
+        transfer(cpi_ctx, stake)?;
+
+        // Transfer opening fee from user vault to treasury
+        if opening_fee > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user_vault.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, opening_fee)?;
+            self.protocol_config.record_fee(opening_fee)?;
+        }
+
+        // Credit the referrer's share of the opening fee, paid out of the treasury on claim
+        let mut referral_rebate = 0;
+        if let Some(referrer) = self.referrer.as_mut() {
+            referral_rebate = self.protocol_config.referral_rebate(opening_fee);
+            referrer.record_referral(amount, referral_rebate)?;
+            self.protocol_config.record_referral_rebate(referral_rebate)?;
+        }
         
         // Update trading pool amounts
-        self.trading_pool.total_active_amount = self.trading_pool.total_active_amount.checked_add(amount)
+        self.trading_pool.total_active_amount = self.trading_pool.total_active_amount.checked_add(stake)
             .ok_or(ErrorCode::MathOverflow)?;
-        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount.checked_add(amount)
+        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount.checked_add(stake)
             .ok_or(ErrorCode::MathOverflow)?;
+        self.trading_pool.add_liability(stake, max_payout)?;
         
         emit!(PositionCreatedEvent {
             position: self.position.key(),
-            user: self.position.user,
-            position_type: self.position.position_type,
-            lower_bound: self.position.lower_bound,
-            upper_bound: self.position.upper_bound,
-            start_time: self.position.start_time,
-            amount: self.position.amount,
-            order_id: self.position.order_id,
+            user: position.user,
+            position_type: position.position_type(),
+            lower_bound: position.lower_bound,
+            upper_bound: position.upper_bound,
+            start_time: position.start_time,
+            amount: position.amount,
+            opening_fee,
+            referrer: self.referrer.as_ref().map(|referrer| referrer.key()),
+            referral_rebate,
+            position_id,
             trading_pool: self.trading_pool.key(),
         });
+        drop(position);
+
+        // Claim rights follow the token from here on
+        if tokenize {
+            let position_mint = self.position_mint.as_ref().ok_or(ErrorCode::MissingPositionTokenAccounts)?;
+            let user_position_token = self.user_position_token.as_ref().ok_or(ErrorCode::MissingPositionTokenAccounts)?;
+            let token_program = self.token_program.as_ref().ok_or(ErrorCode::MissingPositionTokenAccounts)?;
+
+            mint_position_token(
+                &self.position,
+                &position_mint.to_account_info(),
+                &user_position_token.to_account_info(),
+                &token_program.to_account_info(),
+            )?;
+        }
 
-        Ok(())
+        Ok(position_id)
     }
 }
 
@@ -172,6 +322,9 @@ pub struct PositionCreatedEvent {
     pub upper_bound: u64,
     pub start_time: i64,
     pub amount: u64,
-    pub order_id: u64,
+    pub opening_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_rebate: u64,
+    pub position_id: u64,
     pub trading_pool: Pubkey,
 }
\ No newline at end of file
@@ -0,0 +1,403 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{
+    ExposureBook, PositionIndex, PositionState, PositionType, ProtocolConfig, TradingPool, UserStats,
+    VaultState,
+};
+use crate::error::ErrorCode;
+use crate::constants::{MAXIMUM_AGE, MAX_LADDER_RUNGS};
+
+// One band of a ladder
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LadderRung {
+    pub position_type: PositionType,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub amount: u64,
+}
+
+// Position accounts for each rung are passed as remaining accounts, in rung order,
+// at the addresses of the user's next position ids
+#[derive(Accounts)]
+pub struct CreatePositionLadder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // User's personal vault
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    // Assigns the position ids
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Box<Account<'info, TradingPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the opening fees
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // User's running position stats
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    // Index page of the first rung's position id
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + PositionIndex::INIT_SPACE,
+        seeds = [
+            b"position_index".as_ref(),
+            user.key().as_ref(),
+            &PositionIndex::page_of(user_vault_state.next_position_id).to_le_bytes()
+        ],
+        bump
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+
+    // The following index page, only passed when the ladder's ids run onto it
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + PositionIndex::INIT_SPACE,
+        seeds = [
+            b"position_index".as_ref(),
+            user.key().as_ref(),
+            &(PositionIndex::page_of(user_vault_state.next_position_id) + 1).to_le_bytes()
+        ],
+        bump
+    )]
+    pub next_position_index: Option<Box<Account<'info, PositionIndex>>>,
+
+    #[account(
+        mut,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreatePositionLadder<'info> {
+    pub fn create_position_ladder(
+        &mut self,
+        rungs: Vec<LadderRung>,
+        position_accounts: &'info [AccountInfo<'info>],
+        bumps: &CreatePositionLadderBumps,
+    ) -> Result<u64> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+        require!(
+            !rungs.is_empty() && rungs.len() <= MAX_LADDER_RUNGS && position_accounts.len() == rungs.len(),
+            ErrorCode::InvalidLadder
+        );
+
+        let mut total_amount: u64 = 0;
+        for rung in rungs.iter() {
+            require!(rung.lower_bound < rung.upper_bound, ErrorCode::InvalidRange);
+            // Size limits apply to what each rung actually stakes
+            let stake = rung.amount
+                .checked_sub(self.protocol_config.opening_fee(rung.amount))
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.trading_pool.check_position_size(stake)?;
+            total_amount = total_amount.checked_add(rung.amount).ok_or(ErrorCode::MathOverflow)?;
+        }
+        require!(
+            self.user_vault.lamports() >= total_amount,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        // Validate price feed - this ensures the feed ID matches the configured market
+        let clock = Clock::get()?;
+        let _price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+
+        // The ladder is identified by its first position id
+        let ladder_id = self.user_vault_state.next_position_id;
+        let position_space = 8 + PositionState::INIT_SPACE;
+        let position_rent = Rent::get()?.minimum_balance(position_space);
+
+        let mut total_stake: u64 = 0;
+        let mut total_fee: u64 = 0;
+
+        self.user_stats.ensure_initialized(self.user.key(), bumps.user_stats);
+
+        let first_page = PositionIndex::page_of(ladder_id);
+        self.position_index.ensure_initialized(self.user.key(), first_page, bumps.position_index);
+        if let Some(next_position_index) = self.next_position_index.as_mut() {
+            next_position_index.ensure_initialized(
+                self.user.key(),
+                first_page + 1,
+                bumps.next_position_index.unwrap_or_default(),
+            );
+        }
+
+        for (index, (rung, position_info)) in rungs.iter().zip(position_accounts.iter()).enumerate() {
+            let position_id = self.user_vault_state.take_position_id()?;
+            let position_id_bytes = position_id.to_le_bytes();
+            let user_key = self.user.key();
+
+            let (expected_position, bump) = Pubkey::find_program_address(
+                &[b"position", user_key.as_ref(), &position_id_bytes],
+                &crate::ID,
+            );
+            require!(position_info.key() == expected_position, ErrorCode::InvalidLadder);
+
+            let position_seeds = &[
+                b"position".as_ref(),
+                user_key.as_ref(),
+                &position_id_bytes,
+                &[bump],
+            ];
+            let signer_seeds = &[&position_seeds[..]];
+
+            create_position_account(
+                position_info,
+                &self.user.to_account_info(),
+                &self.system_program.to_account_info(),
+                position_rent,
+                position_space,
+                signer_seeds,
+            )?;
+
+            if PositionIndex::page_of(position_id) == first_page {
+                self.position_index.insert(position_id)?;
+            } else {
+                self.next_position_index
+                    .as_mut()
+                    .ok_or(ErrorCode::InvalidPositionIndex)?
+                    .insert(position_id)?;
+            }
+
+            // Opening fee is taken out of each rung, the rest is staked
+            let opening_fee = self.protocol_config.opening_fee(rung.amount);
+            let stake = rung.amount.checked_sub(opening_fee).ok_or(ErrorCode::MathOverflow)?;
+
+            let loader = AccountLoader::<PositionState>::try_from_unchecked(&crate::ID, position_info)?;
+            let max_payout = {
+                let mut position = loader.load_init()?;
+                position.initialize(
+                    user_key,
+                    rung.position_type,
+                    rung.lower_bound,
+                    rung.upper_bound,
+                    clock.unix_timestamp,
+                    self.protocol_config.position_duration,
+                    position_id,
+                    stake,
+                    bump,
+                )?;
+                position.ladder_rung = (index + 1) as u8;
+                position.max_payout()
+            };
+            // Writes the discriminator, as init does for accounts in the context
+            loader.exit(&crate::ID)?;
+
+            // Each rung is checked against the user's caps and the pool including the earlier rungs
+            self.user_vault_state.record_open(stake, max_payout, &self.trading_pool)?;
+            self.trading_pool.check_solvency(stake, max_payout)?;
+            self.exposure_book.add_exposure(rung.position_type, rung.lower_bound, rung.upper_bound, stake)?;
+
+            self.trading_pool.total_active_amount = self.trading_pool.total_active_amount.checked_add(stake)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount.checked_add(stake)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.trading_pool.add_liability(stake, max_payout)?;
+
+            self.user_stats.record_open(stake, opening_fee)?;
+
+            total_stake = total_stake.checked_add(stake).ok_or(ErrorCode::MathOverflow)?;
+            total_fee = total_fee.checked_add(opening_fee).ok_or(ErrorCode::MathOverflow)?;
+
+            emit!(LadderPositionCreatedEvent {
+                ladder_id,
+                position: position_info.key(),
+                position_id,
+                user: user_key,
+                position_type: rung.position_type,
+                lower_bound: rung.lower_bound,
+                upper_bound: rung.upper_bound,
+                start_time: clock.unix_timestamp,
+                amount: stake,
+                opening_fee,
+                trading_pool: self.trading_pool.key(),
+            });
+        }
+
+        // One transfer for every rung's stake, and one for the fees
+        let user_vault_seeds = &[
+            b"vault".as_ref(),
+            self.user_vault_state.to_account_info().key.as_ref(),
+            &[self.user_vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&user_vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.user_vault.to_account_info(),
+                to: self.trading_pool_vault.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, total_stake)?;
+
+        if total_fee > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user_vault.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, total_fee)?;
+            self.protocol_config.record_fee(total_fee)?;
+        }
+
+        emit!(LadderCreatedEvent {
+            ladder_id,
+            user: self.user.key(),
+            rungs: rungs.len() as u8,
+            total_amount,
+            total_fee,
+        });
+
+        Ok(ladder_id)
+    }
+}
+
+//<------------------Helper functions-------------------->
+
+// Position addresses are predictable, so one may already hold lamports. Like init,
+// top it up to rent and allocate and assign it instead of creating it.
+// signer_seeds covers the position and, when it is a PDA, the payer
+pub fn create_position_account<'info>(
+    position_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: u64,
+    space: usize,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let current_lamports = position_info.lamports();
+    if current_lamports == 0 {
+        let cpi_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: position_info.clone(),
+            },
+            signer_seeds,
+        );
+
+        return create_account(cpi_ctx, rent, space as u64, &crate::ID);
+    }
+
+    let top_up = rent.saturating_sub(current_lamports);
+    if top_up > 0 {
+        let cpi_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            Transfer {
+                from: payer.clone(),
+                to: position_info.clone(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, top_up)?;
+    }
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.clone(),
+        Allocate {
+            account_to_allocate: position_info.clone(),
+        },
+        signer_seeds,
+    );
+    allocate(cpi_ctx, space as u64)?;
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.clone(),
+        Assign {
+            account_to_assign: position_info.clone(),
+        },
+        signer_seeds,
+    );
+    assign(cpi_ctx, &crate::ID)
+}
+
+#[event]
+pub struct LadderPositionCreatedEvent {
+    pub ladder_id: u64,
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub user: Pubkey,
+    pub position_type: PositionType,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub start_time: i64,
+    pub amount: u64,
+    pub opening_fee: u64,
+    pub trading_pool: Pubkey,
+}
+
+#[event]
+pub struct LadderCreatedEvent {
+    pub ladder_id: u64,
+    pub user: Pubkey,
+    pub rungs: u8,
+    pub total_amount: u64,
+    pub total_fee: u64,
+}
@@ -0,0 +1,237 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{
+    OrderBook, PairedPosition, PositionStatus, PositionType, ProtocolConfig, Quote, Role, Roles,
+    UsedQuote, VaultState,
+};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(quote: Quote)]
+pub struct CreatePositionWithQuote<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Only used for seed and validation, the quote signature authorizes the maker
+    #[account(address = quote.maker @ ErrorCode::InvalidQuote)]
+    pub maker: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", order_book.trading_pool.as_ref()],
+        bump = order_book.bump,
+        constraint = order_book.key() == quote.order_book @ ErrorCode::InvalidQuote,
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PairedPosition::INIT_SPACE,
+        seeds = [
+            b"paired_position".as_ref(),
+            order_book.key().as_ref(),
+            &order_book.next_match_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub paired_position: Box<Account<'info, PairedPosition>>,
+
+    // Holds the taker's stake and the maker's collateral until claimed
+    #[account(
+        mut,
+        seeds = [b"paired_escrow", paired_position.key().as_ref()],
+        bump
+    )]
+    pub paired_escrow: SystemAccount<'info>,
+
+    // Replay protection, creation fails if the nonce was already used
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UsedQuote::INIT_SPACE,
+        seeds = [b"used_quote", maker.key().as_ref(), &quote.nonce.to_le_bytes()],
+        bump
+    )]
+    pub used_quote: Box<Account<'info, UsedQuote>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", maker_vault_state.key().as_ref()],
+        bump = maker_vault_state.vault_bump,
+    )]
+    pub maker_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", maker.key().as_ref()],
+        bump = maker_vault_state.state_bump
+    )]
+    pub maker_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::MarketMaker, &maker.key())? @ ErrorCode::MakerNotWhitelisted,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    /// CHECK: Instructions sysvar, read to find the ed25519 signature check
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreatePositionWithQuote<'info> {
+    pub fn create_position_with_quote(
+        &mut self,
+        quote: Quote,
+        bumps: &CreatePositionWithQuoteBumps,
+    ) -> Result<()> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+        require!(quote.lower_bound < quote.upper_bound, ErrorCode::InvalidRange);
+        require!(quote.size >= VaultState::MIN_ORDER_AMOUNT, ErrorCode::AmountTooSmall);
+        require!(quote.maker != self.user.key(), ErrorCode::InvalidQuote);
+
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(current_time < quote.expiry, ErrorCode::QuoteExpired);
+
+        // The maker's signature is checked by the ed25519 program in the previous instruction
+        let current_index = load_current_index_checked(&self.instructions)?;
+        require!(current_index > 0, ErrorCode::InvalidQuoteSignature);
+        let signature_ix = load_instruction_at_checked(current_index as usize - 1, &self.instructions)?;
+        quote.verify_signature(&signature_ix)?;
+
+        let taker_stake = quote.size;
+        let maker_stake = quote.maker_stake()?;
+        require!(maker_stake > 0, ErrorCode::InvalidQuote);
+        require!(
+            self.user_vault.lamports() >= taker_stake && self.maker_vault.lamports() >= maker_stake,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        self.used_quote.set_inner(UsedQuote {
+            version: UsedQuote::VERSION,
+            maker: quote.maker,
+            nonce: quote.nonce,
+            bump: bumps.used_quote,
+            reserved: [0; 64],
+        });
+
+        // Maker takes the other side of the band
+        let (stay_in_user, breakout_user, stay_in_stake, breakout_stake) = match quote.position_type {
+            PositionType::StayIn => (self.user.key(), quote.maker, taker_stake, maker_stake),
+            PositionType::Breakout => (quote.maker, self.user.key(), maker_stake, taker_stake),
+        };
+
+        let match_id = self.order_book.take_match_id()?;
+
+        self.paired_position.set_inner(PairedPosition {
+            version: PairedPosition::VERSION,
+            match_id,
+            order_book: self.order_book.key(),
+            stay_in_user,
+            breakout_user,
+            lower_bound: quote.lower_bound,
+            upper_bound: quote.upper_bound,
+            stay_in_stake,
+            breakout_stake,
+            start_time: current_time,
+            duration: self.protocol_config.position_duration,
+            status: PositionStatus::Active,
+            settlement_data: None,
+            stay_in_payout: 0,
+            breakout_payout: 0,
+            stay_in_claimed: false,
+            breakout_claimed: false,
+            bump: bumps.paired_position,
+            escrow_bump: bumps.paired_escrow,
+            rent_payer: Pubkey::default(),
+            reserved: [0; 32],
+        });
+
+        // Escrow rent is refunded on the last claim
+        self.paired_position.fund_escrow_rent(
+            &self.paired_escrow.to_account_info(),
+            &self.user.to_account_info(),
+            &self.system_program.to_account_info(),
+        )?;
+
+        // Taker and maker stake from their own vaults into the escrow
+        for (vault, vault_state, stake) in [
+            (&self.user_vault, &self.user_vault_state, taker_stake),
+            (&self.maker_vault, &self.maker_vault_state, maker_stake),
+        ] {
+            let vault_seeds = &[
+                b"vault".as_ref(),
+                vault_state.to_account_info().key.as_ref(),
+                &[vault_state.vault_bump],
+            ];
+            let signer_seeds = &[&vault_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: self.paired_escrow.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, stake)?;
+        }
+
+        emit!(QuoteFilledEvent {
+            paired_position: self.paired_position.key(),
+            match_id,
+            maker: quote.maker,
+            taker: self.user.key(),
+            position_type: quote.position_type,
+            lower_bound: quote.lower_bound,
+            upper_bound: quote.upper_bound,
+            odds_bps: quote.odds_bps,
+            taker_stake,
+            maker_stake,
+            nonce: quote.nonce,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct QuoteFilledEvent {
+    pub paired_position: Pubkey,
+    pub match_id: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub position_type: PositionType,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub odds_bps: u32,
+    pub taker_stake: u64,
+    pub maker_stake: u64,
+    pub nonce: u64,
+}
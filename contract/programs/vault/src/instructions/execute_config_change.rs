@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::state::{ConfigChange, ProtocolConfig, QueuedChange};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"queued_change".as_ref(),
+            protocol_config.key().as_ref(),
+            &queued_change.change_id.to_le_bytes()
+        ],
+        bump = queued_change.bump,
+        has_one = proposer,
+        close = proposer,
+    )]
+    pub queued_change: Account<'info, QueuedChange>,
+
+    /// CHECK: Receives the queued change rent, validated by has_one
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
+}
+
+impl<'info> ExecuteConfigChange<'info> {
+    pub fn execute_config_change(&mut self) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            self.queued_change.is_executable(current_time),
+            ErrorCode::TimelockNotExpired
+        );
+
+        let change = self.queued_change.change;
+        self.protocol_config.apply_change(&change)?;
+
+        emit!(ConfigChangeExecutedEvent {
+            change_id: self.queued_change.change_id,
+            change,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ConfigChangeExecutedEvent {
+    pub change_id: u64,
+    pub change: ConfigChange,
+}
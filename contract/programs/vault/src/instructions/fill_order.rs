@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{
+    ExposureBook, Order, OrderBook, OrderStatus, PositionIndex, PositionState, ProtocolConfig, Role,
+    Roles, TradingPool, UserStats, VaultState,
+};
+use crate::error::ErrorCode;
+use crate::constants::MAXIMUM_AGE;
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct FillOrder<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// CHECK: Only used for seed and validation
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", order_book.trading_pool.as_ref()],
+        bump = order_book.bump,
+        constraint = order_book.trading_pool == trading_pool.key(),
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump = order_book.vault_bump
+    )]
+    pub order_book_vault: SystemAccount<'info>,
+
+    /// CHECK: Deserialized in the handler so unknown orders fail with OrderNotFound
+    #[account(
+        mut,
+        seeds = [b"order".as_ref(), order_book.key().as_ref(), &order_id.to_le_bytes()],
+        bump
+    )]
+    pub order: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + PositionState::INIT_SPACE,
+        seeds = [
+            b"position".as_ref(),
+            user.key().as_ref(),
+            &user_vault_state.next_position_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub position: AccountLoader<'info, PositionState>,
+
+    // Assigns the position id
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Box<Account<'info, TradingPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the opening fee
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // User's running position stats
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    // Index page that lists the new position id
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + PositionIndex::INIT_SPACE,
+        seeds = [
+            b"position_index".as_ref(),
+            user.key().as_ref(),
+            &PositionIndex::page_of(user_vault_state.next_position_id).to_le_bytes()
+        ],
+        bump
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FillOrder<'info> {
+    pub fn fill_order(
+        &mut self,
+        order_id: u64,
+        amount: u64,
+        bumps: &FillOrderBumps,
+    ) -> Result<()> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+
+        // Opening fee is taken out of the fill, the rest is staked
+        let opening_fee = self.protocol_config.opening_fee(amount);
+        let stake = amount.checked_sub(opening_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        // Each fill opens its own position, so it is held to the same size limits
+        self.trading_pool.check_position_size(stake)?;
+
+        // Unknown order ids have no order account behind their address
+        require!(
+            self.order.owner == &crate::ID && !self.order.data_is_empty(),
+            ErrorCode::OrderNotFound
+        );
+        let mut order = {
+            let data = self.order.try_borrow_data()?;
+            Order::try_deserialize(&mut &data[..]).map_err(|_| error!(ErrorCode::OrderNotFound))?
+        };
+        require!(order.owner == self.user.key(), ErrorCode::UnauthorizedAccess);
+
+        let clock = Clock::get()?;
+        require!(!order.is_expired(clock.unix_timestamp), ErrorCode::OrderExpired);
+
+        // Validate price feed - this ensures the feed ID matches the configured market
+        let _price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+
+        order.fill(amount)?;
+        if order.status == OrderStatus::Filled {
+            self.order_book.close_order()?;
+        }
+
+        let position_id = self.user_vault_state.take_position_id()?;
+        self.position_index.ensure_initialized(self.user.key(), PositionIndex::page_of(position_id), bumps.position_index);
+        self.position_index.insert(position_id)?;
+
+        let mut position = self.position.load_init()?;
+        position.initialize(
+            order.owner,
+            order.position_type,
+            order.lower_bound,
+            order.upper_bound,
+            clock.unix_timestamp,
+            self.protocol_config.position_duration,
+            position_id,
+            stake,
+            bumps.position,
+        )?;
+
+        self.user_stats.ensure_initialized(order.owner, bumps.user_stats);
+        self.user_stats.record_open(stake, opening_fee)?;
+
+        // Same user caps and pool risk checks as create_position
+        let max_payout = position.max_payout();
+        self.user_vault_state.record_open(stake, max_payout, &self.trading_pool)?;
+        self.trading_pool.check_solvency(stake, max_payout)?;
+        self.exposure_book.add_exposure(order.position_type, order.lower_bound, order.upper_bound, stake)?;
+
+        // Move the filled collateral out of the order book vault
+        let order_book_key = self.order_book.key();
+        let vault_seeds = &[
+            b"order_book_vault".as_ref(),
+            order_book_key.as_ref(),
+            &[self.order_book.vault_bump],
+        ];
+        let signer_seeds = &[&vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.order_book_vault.to_account_info(),
+                to: self.trading_pool_vault.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, stake)?;
+
+        if opening_fee > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.order_book_vault.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, opening_fee)?;
+            self.protocol_config.record_fee(opening_fee)?;
+        }
+
+        self.trading_pool.total_active_amount = self.trading_pool.total_active_amount.checked_add(stake)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount.checked_add(stake)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.trading_pool.add_liability(stake, max_payout)?;
+
+        // Write the updated fill amounts back to the order
+        {
+            let mut data = self.order.try_borrow_mut_data()?;
+            order.try_serialize(&mut &mut data[..])?;
+        }
+
+        emit!(OrderFilledEvent {
+            order: self.order.key(),
+            order_id,
+            position: self.position.key(),
+            position_id,
+            user: order.owner,
+            fill_amount: amount,
+            opening_fee,
+            filled_amount: order.filled_amount,
+            remaining_amount: order.remaining_amount(),
+            trading_pool: self.trading_pool.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct OrderFilledEvent {
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub position: Pubkey,
+    pub position_id: u64,
+    pub user: Pubkey,
+    pub fill_amount: u64,
+    pub opening_fee: u64,
+    pub filled_amount: u64,
+    pub remaining_amount: u64,
+    pub trading_pool: Pubkey,
+}
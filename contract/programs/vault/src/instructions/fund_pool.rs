@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{ProtocolConfig, TradingPool};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct FundPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // House liquidity is managed by the protocol authority
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FundPool<'info> {
+    // House top-up. No shares are minted, so it accrues to existing LP shares
+    pub fn fund_pool(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::AmountTooSmall);
+
+        let cpi_ctx = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.authority.to_account_info(),
+                to: self.trading_pool_vault.to_account_info(),
+            },
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(PoolFundedEvent {
+            trading_pool: self.trading_pool.key(),
+            authority: self.authority.key(),
+            amount,
+            total_pool_amount: self.trading_pool.total_pool_amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PoolFundedEvent {
+    pub trading_pool: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_pool_amount: u64,
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use crate::state::{ExposureBook, TradingPool, EXPOSURE_BUCKETS};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct InitExposureBook<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ExposureBook::INIT_SPACE,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitExposureBook<'info> {
+    pub fn init_exposure_book(
+        &mut self,
+        base_price: u64,
+        bucket_width: u64,
+        max_net_exposure: u64,
+        bumps: &InitExposureBookBumps,
+    ) -> Result<()> {
+        require!(bucket_width > 0, ErrorCode::InvalidExposureConfig);
+
+        // Bucket layout is fixed for the life of the book so that settling
+        // a position removes exactly what creating it added
+        self.exposure_book.set_inner(ExposureBook {
+            version: ExposureBook::VERSION,
+            trading_pool: self.trading_pool.key(),
+            base_price,
+            bucket_width,
+            max_net_exposure,
+            stay_in_exposure: [0; EXPOSURE_BUCKETS],
+            breakout_exposure: [0; EXPOSURE_BUCKETS],
+            bump: bumps.exposure_book,
+            reserved: [0; 64],
+        });
+
+        emit!(ExposureBookCreatedEvent {
+            exposure_book: self.exposure_book.key(),
+            trading_pool: self.trading_pool.key(),
+            base_price,
+            bucket_width,
+            max_net_exposure,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ExposureBookCreatedEvent {
+    pub exposure_book: Pubkey,
+    pub trading_pool: Pubkey,
+    pub base_price: u64,
+    pub bucket_width: u64,
+    pub max_net_exposure: u64,
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+use crate::state::TradingPool;
+use crate::error::ErrorCode;
+
+pub const LP_MINT_DECIMALS: u8 = 9;
+
+#[derive(Accounts)]
+pub struct InitLpMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+        constraint = trading_pool.authority == admin.key() @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    // Share token minted to liquidity providers, the pool PDA is the mint authority
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"lp_mint", trading_pool.key().as_ref()],
+        bump,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::authority = trading_pool,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    // Receives the shares backing liquidity already sitting in the pool
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = lp_mint,
+        associated_token::authority = admin,
+    )]
+    pub admin_lp_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitLpMint<'info> {
+    pub fn init_lp_mint(&mut self, bumps: &InitLpMintBumps) -> Result<()> {
+        self.trading_pool.lp_mint_bump = bumps.lp_mint;
+
+        // House liquidity funded before the mint existed is owned by the admin,
+        // otherwise the first LP would receive it for free
+        let seed_shares = self.trading_pool.nav();
+
+        if seed_shares > 0 {
+            let pool_seeds = &[b"trading_pool".as_ref(), &[self.trading_pool.bump]];
+            let signer_seeds = &[&pool_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.lp_mint.to_account_info(),
+                    to: self.admin_lp_token.to_account_info(),
+                    authority: self.trading_pool.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            mint_to(cpi_ctx, seed_shares)?;
+        }
+
+        emit!(LpMintCreatedEvent {
+            trading_pool: self.trading_pool.key(),
+            lp_mint: self.lp_mint.key(),
+            seed_shares,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct LpMintCreatedEvent {
+    pub trading_pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub seed_shares: u64,
+}
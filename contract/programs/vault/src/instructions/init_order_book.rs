@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{OrderBook, TradingPool};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct InitOrderBook<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + OrderBook::INIT_SPACE,
+        seeds = [b"order_book", trading_pool.key().as_ref()],
+        bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    // Holds collateral locked by open orders
+    #[account(
+        mut,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub order_book_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitOrderBook<'info> {
+    pub fn init_order_book(&mut self, bumps: &InitOrderBookBumps) -> Result<()> {
+        self.order_book.set_inner(OrderBook {
+            version: OrderBook::VERSION,
+            trading_pool: self.trading_pool.key(),
+            next_order_id: 0,
+            next_match_id: 0,
+            open_orders: 0,
+            bump: bumps.order_book,
+            vault_bump: bumps.order_book_vault,
+            reserved: [0; 64],
+        });
+
+        // Keep the vault rent exempt so partial fills can leave any remainder behind
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let shortfall = rent_exempt_minimum.saturating_sub(self.order_book_vault.lamports());
+
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.authority.to_account_info(),
+                    to: self.order_book_vault.to_account_info(),
+                },
+            );
+
+            transfer(cpi_ctx, shortfall)?;
+        }
+
+        emit!(OrderBookCreatedEvent {
+            order_book: self.order_book.key(),
+            trading_pool: self.trading_pool.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct OrderBookCreatedEvent {
+    pub order_book: Pubkey,
+    pub trading_pool: Pubkey,
+}
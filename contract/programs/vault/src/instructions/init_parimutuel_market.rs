@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::state::{ParimutuelMarket, TradingPool};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct InitParimutuelMarket<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ParimutuelMarket::INIT_SPACE,
+        seeds = [b"parimutuel_market", trading_pool.key().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, ParimutuelMarket>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitParimutuelMarket<'info> {
+    pub fn init_parimutuel_market(
+        &mut self,
+        epoch_length: i64,
+        stake_window: i64,
+        band_width_bps: u16,
+        bumps: &InitParimutuelMarketBumps,
+    ) -> Result<()> {
+        ParimutuelMarket::validate(epoch_length, stake_window, band_width_bps)?;
+
+        self.market.set_inner(ParimutuelMarket {
+            version: ParimutuelMarket::VERSION,
+            trading_pool: self.trading_pool.key(),
+            epoch_length,
+            stake_window,
+            band_width_bps,
+            bump: bumps.market,
+            reserved: [0; 64],
+        });
+
+        emit!(ParimutuelMarketCreatedEvent {
+            market: self.market.key(),
+            trading_pool: self.trading_pool.key(),
+            epoch_length,
+            stake_window,
+            band_width_bps,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ParimutuelMarketCreatedEvent {
+    pub market: Pubkey,
+    pub trading_pool: Pubkey,
+    pub epoch_length: i64,
+    pub stake_window: i64,
+    pub band_width_bps: u16,
+}
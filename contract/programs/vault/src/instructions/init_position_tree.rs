@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::{PositionTree, TradingPool};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(tree_id: u64)]
+pub struct InitPositionTree<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    // A new tree is added whenever the previous one fills up
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PositionTree::INIT_SPACE,
+        seeds = [b"position_tree", trading_pool.key().as_ref(), &tree_id.to_le_bytes()],
+        bump
+    )]
+    pub position_tree: AccountLoader<'info, PositionTree>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitPositionTree<'info> {
+    pub fn init_position_tree(&mut self, tree_id: u64, bumps: &InitPositionTreeBumps) -> Result<()> {
+        let mut position_tree = self.position_tree.load_init()?;
+        position_tree.initialize(tree_id, self.trading_pool.key(), bumps.position_tree);
+
+        emit!(PositionTreeCreatedEvent {
+            position_tree: self.position_tree.key(),
+            trading_pool: self.trading_pool.key(),
+            tree_id,
+            capacity: PositionTree::CAPACITY,
+            root: position_tree.root(),
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PositionTreeCreatedEvent {
+    pub position_tree: Pubkey,
+    pub trading_pool: Pubkey,
+    pub tree_id: u64,
+    pub capacity: u64,
+    pub root: [u8; 32],
+}
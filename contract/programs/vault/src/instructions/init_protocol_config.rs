@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+use crate::program::Vault;
+use crate::constants::{BTC_FEED_ID, DEFAULT_POSITION_DURATION, DEFAULT_TIMELOCK_DELAY};
+use crate::state::{ProtocolConfig, Roles};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct InitProtocolConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // Only the program's upgrade authority can bootstrap the protocol admin
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub program: Program<'info, Vault>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProtocolConfig::INIT_SPACE,
+        seeds = [b"protocol_config"],
+        bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Roles::INIT_SPACE,
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump
+    )]
+    pub roles: Account<'info, Roles>,
+
+    // Treasury PDA that collects protocol fees
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitProtocolConfig<'info> {
+    pub fn init_protocol_config(
+        &mut self,
+        opening_fee_bps: u16,
+        winnings_fee_bps: u16,
+        referral_share_bps: u16,
+        bumps: &InitProtocolConfigBumps,
+    ) -> Result<()> {
+        ProtocolConfig::validate_fees(opening_fee_bps, winnings_fee_bps, referral_share_bps)?;
+
+        self.protocol_config.set_inner(ProtocolConfig {
+            version: ProtocolConfig::VERSION,
+            authority: self.authority.key(),
+            pending_authority: None,
+            price_feed_id: get_feed_id_from_hex(BTC_FEED_ID)?,
+            position_duration: DEFAULT_POSITION_DURATION,
+            timelock_delay: DEFAULT_TIMELOCK_DELAY,
+            next_change_id: 0,
+            opening_fee_bps,
+            winnings_fee_bps,
+            referral_share_bps,
+            total_fees_collected: 0,
+            total_fees_withdrawn: 0,
+            total_referral_owed: 0,
+            paused: false,
+            bump: bumps.protocol_config,
+            treasury_bump: bumps.treasury,
+            reserved: [0; 64],
+        });
+
+        self.roles.set_inner(Roles {
+            version: Roles::VERSION,
+            risk_managers: Vec::new(),
+            pausers: Vec::new(),
+            keepers: Vec::new(),
+            fee_collectors: Vec::new(),
+            market_makers: Vec::new(),
+            bump: bumps.roles,
+            reserved: [0; 64],
+        });
+
+        // Fund the treasury up to rent exemption so small fees can be transferred in
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let shortfall = rent_exempt_minimum.saturating_sub(self.treasury.lamports());
+
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.authority.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+            );
+
+            transfer(cpi_ctx, shortfall)?;
+        }
+
+        emit!(FeesUpdatedEvent {
+            protocol_config: self.protocol_config.key(),
+            opening_fee_bps,
+            winnings_fee_bps,
+            referral_share_bps,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct FeesUpdatedEvent {
+    pub protocol_config: Pubkey,
+    pub opening_fee_bps: u16,
+    pub winnings_fee_bps: u16,
+    pub referral_share_bps: u16,
+}
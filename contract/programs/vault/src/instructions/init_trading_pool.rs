@@ -1,6 +1,8 @@
 // init_trading_pool.rs - Add this to your instructions folder
 use anchor_lang::prelude::*;
-use crate::state::TradingPool;
+use crate::state::{ProtocolConfig, TradingPool, VaultState};
+use crate::constants::DEFAULT_MAX_UTILIZATION_BPS;
+use crate::error::ErrorCode;
 
 
 #[derive(Accounts)]
@@ -8,6 +10,13 @@ pub struct InitTradingPool<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        constraint = protocol_config.authority == admin.key() @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
     #[account(
         init,
         payer = admin,
@@ -30,11 +39,23 @@ pub struct InitTradingPool<'info> {
 impl<'info> InitTradingPool<'info> {
     pub fn initialize(&mut self, bumps: &InitTradingPoolBumps) -> Result<()> {
         
+        self.trading_pool.version = TradingPool::VERSION;
         self.trading_pool.authority = self.admin.key();
+        self.trading_pool.pending_authority = None;
         self.trading_pool.total_active_amount = 0;
         self.trading_pool.total_pool_amount = 0;
+        self.trading_pool.worst_case_liability = 0;
+        self.trading_pool.expected_liability = 0;
+        self.trading_pool.max_utilization_bps = DEFAULT_MAX_UTILIZATION_BPS;
+        self.trading_pool.max_user_open_positions = 0;
+        self.trading_pool.max_user_notional = 0;
+        self.trading_pool.max_user_payout = 0;
+        self.trading_pool.min_position_size = VaultState::MIN_ORDER_AMOUNT;
+        self.trading_pool.max_position_size = 0;
+        self.trading_pool.max_position_liquidity_bps = 0;
         self.trading_pool.bump = bumps.trading_pool;
         self.trading_pool.vault_bump = bumps.trading_pool_vault;
+        self.trading_pool.lp_mint_bump = 0;
 
         emit!(TradingPoolCreatedEvent {
             pool: self.trading_pool.key(),
@@ -28,7 +28,12 @@ pub struct Initialize<'info> {
 impl<'info> Initialize<'info> {
     pub fn initialize_vault(&mut self, bumps: &InitializeBumps) -> Result<()> {
 
+        self.vault_state.version = VaultState::VERSION;
         self.vault_state.authority = self.user.key();
+        self.vault_state.next_position_id = 0;
+        self.vault_state.open_positions = 0;
+        self.vault_state.open_notional = 0;
+        self.vault_state.open_max_payout = 0;
         self.vault_state.vault_bump = bumps.vault;
         self.vault_state.state_bump = bumps.vault_state;
         
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+use crate::state::{ProtocolConfig, TradingPool, VaultState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct LpDeposit<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_mint", trading_pool.key().as_ref()],
+        bump = trading_pool.lp_mint_bump,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = lp_mint,
+        associated_token::authority = user,
+    )]
+    pub user_lp_token: Account<'info, TokenAccount>,
+
+    // Holds shares locked by the deposit that restarts an empty supply, the
+    // pool PDA never transfers out of it
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = lp_mint,
+        associated_token::authority = trading_pool,
+    )]
+    pub pool_lp_token: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LpDeposit<'info> {
+    pub fn lp_deposit(&mut self, amount: u64) -> Result<()> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+        require!(
+            amount >= VaultState::MIN_ORDER_AMOUNT,
+            ErrorCode::AmountTooSmall
+        );
+
+        // Price shares before the deposit changes the NAV
+        let shares = self.trading_pool.shares_for_deposit(amount, self.lp_mint.supply)?;
+        require!(shares > 0, ErrorCode::AmountTooSmall);
+        let locked_shares = self.trading_pool.locked_shares_for_deposit(self.lp_mint.supply)?;
+
+        // Transfer funds from provider to trading pool vault
+        let cpi_ctx = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.user.to_account_info(),
+                to: self.trading_pool_vault.to_account_info(),
+            },
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        // Mint pool shares to the provider
+        let pool_seeds = &[b"trading_pool".as_ref(), &[self.trading_pool.bump]];
+        let signer_seeds = &[&pool_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.lp_mint.to_account_info(),
+                to: self.user_lp_token.to_account_info(),
+                authority: self.trading_pool.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        mint_to(cpi_ctx, shares)?;
+
+        if locked_shares > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.lp_mint.to_account_info(),
+                    to: self.pool_lp_token.to_account_info(),
+                    authority: self.trading_pool.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            mint_to(cpi_ctx, locked_shares)?;
+        }
+
+        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(LpDepositEvent {
+            user: self.user.key(),
+            trading_pool: self.trading_pool.key(),
+            amount,
+            shares,
+            locked_shares,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct LpDepositEvent {
+    pub user: Pubkey,
+    pub trading_pool: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub locked_shares: u64,
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+use crate::state::TradingPool;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct LpWithdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_mint", trading_pool.key().as_ref()],
+        bump = trading_pool.lp_mint_bump,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = user,
+    )]
+    pub user_lp_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LpWithdraw<'info> {
+    pub fn lp_withdraw(&mut self, shares: u64) -> Result<()> {
+        require!(shares > 0, ErrorCode::AmountTooSmall);
+        require!(
+            self.user_lp_token.amount >= shares,
+            ErrorCode::InsufficientFunds
+        );
+
+        // Price shares before the burn changes the supply
+        let amount = self.trading_pool.amount_for_shares(shares, self.lp_mint.supply)?;
+        require!(amount > 0, ErrorCode::AmountTooSmall);
+
+        // Funds backing open positions' max payouts stay in the pool
+        require!(
+            amount <= self.trading_pool.free_liquidity(),
+            ErrorCode::InsufficientPoolLiquidity
+        );
+        require!(
+            self.trading_pool_vault.lamports() >= amount,
+            ErrorCode::InsufficientPoolBalance
+        );
+
+        // Burn the provider's shares
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.lp_mint.to_account_info(),
+                from: self.user_lp_token.to_account_info(),
+                authority: self.user.to_account_info(),
+            },
+        );
+
+        burn(cpi_ctx, shares)?;
+
+        // Transfer funds from trading pool vault to provider
+        let pool_vault_seeds = &[
+            b"trading_pool_vault",
+            self.trading_pool.to_account_info().key.as_ref(),
+            &[self.trading_pool.vault_bump],
+        ];
+        let signer_seeds = &[&pool_vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.trading_pool_vault.to_account_info(),
+                to: self.user.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(LpWithdrawEvent {
+            user: self.user.key(),
+            trading_pool: self.trading_pool.key(),
+            shares,
+            amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct LpWithdrawEvent {
+    pub user: Pubkey,
+    pub trading_pool: Pubkey,
+    pub shares: u64,
+    pub amount: u64,
+}
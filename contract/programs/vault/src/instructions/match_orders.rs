@@ -0,0 +1,200 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{
+    Order, OrderBook, OrderStatus, PairedPosition, PositionStatus, PositionType, ProtocolConfig, Role, Roles,
+};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", order_book.trading_pool.as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump = order_book.vault_bump
+    )]
+    pub order_book_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            order_book.key().as_ref(),
+            &stay_in_order.order_id.to_le_bytes()
+        ],
+        bump = stay_in_order.bump,
+        constraint = stay_in_order.position_type == PositionType::StayIn @ ErrorCode::OrdersNotMatchable,
+    )]
+    pub stay_in_order: Box<Account<'info, Order>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            order_book.key().as_ref(),
+            &breakout_order.order_id.to_le_bytes()
+        ],
+        bump = breakout_order.bump,
+        constraint = breakout_order.position_type == PositionType::Breakout @ ErrorCode::OrdersNotMatchable,
+    )]
+    pub breakout_order: Box<Account<'info, Order>>,
+
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + PairedPosition::INIT_SPACE,
+        seeds = [
+            b"paired_position".as_ref(),
+            order_book.key().as_ref(),
+            &order_book.next_match_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub paired_position: Box<Account<'info, PairedPosition>>,
+
+    // Holds both stakes until the paired position is claimed
+    #[account(
+        mut,
+        seeds = [b"paired_escrow", paired_position.key().as_ref()],
+        bump
+    )]
+    pub paired_escrow: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MatchOrders<'info> {
+    pub fn match_orders(&mut self, bumps: &MatchOrdersBumps) -> Result<()> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+
+        let stay_in_order = &self.stay_in_order;
+        let breakout_order = &self.breakout_order;
+
+        require!(
+            stay_in_order.lower_bound == breakout_order.lower_bound
+                && stay_in_order.upper_bound == breakout_order.upper_bound
+                && stay_in_order.owner != breakout_order.owner,
+            ErrorCode::OrdersNotMatchable
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            !stay_in_order.is_expired(current_time) && !breakout_order.is_expired(current_time),
+            ErrorCode::OrderExpired
+        );
+
+        // Fill as much as both orders have left
+        let fill_amount = stay_in_order.remaining_amount().min(breakout_order.remaining_amount());
+        require!(fill_amount > 0, ErrorCode::OrderNotOpen);
+
+        self.stay_in_order.fill(fill_amount)?;
+        self.breakout_order.fill(fill_amount)?;
+
+        if self.stay_in_order.status != OrderStatus::Open {
+            self.order_book.close_order()?;
+        }
+        if self.breakout_order.status != OrderStatus::Open {
+            self.order_book.close_order()?;
+        }
+
+        let match_id = self.order_book.take_match_id()?;
+
+        self.paired_position.set_inner(PairedPosition {
+            version: PairedPosition::VERSION,
+            match_id,
+            order_book: self.order_book.key(),
+            stay_in_user: self.stay_in_order.owner,
+            breakout_user: self.breakout_order.owner,
+            lower_bound: self.stay_in_order.lower_bound,
+            upper_bound: self.stay_in_order.upper_bound,
+            stay_in_stake: fill_amount,
+            breakout_stake: fill_amount,
+            start_time: current_time,
+            duration: self.protocol_config.position_duration,
+            status: PositionStatus::Active,
+            settlement_data: None,
+            stay_in_payout: 0,
+            breakout_payout: 0,
+            stay_in_claimed: false,
+            breakout_claimed: false,
+            bump: bumps.paired_position,
+            escrow_bump: bumps.paired_escrow,
+            rent_payer: Pubkey::default(),
+            reserved: [0; 32],
+        });
+
+        // Keeper keeps the escrow rent exempt, refunded on the last claim
+        self.paired_position.fund_escrow_rent(
+            &self.paired_escrow.to_account_info(),
+            &self.keeper.to_account_info(),
+            &self.system_program.to_account_info(),
+        )?;
+
+        // Move both stakes from the order book vault into the escrow
+        let escrow_amount = self.paired_position.escrow_amount()?;
+
+        let order_book_key = self.order_book.key();
+        let vault_seeds = &[
+            b"order_book_vault".as_ref(),
+            order_book_key.as_ref(),
+            &[self.order_book.vault_bump],
+        ];
+        let signer_seeds = &[&vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.order_book_vault.to_account_info(),
+                to: self.paired_escrow.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, escrow_amount)?;
+
+        emit!(OrdersMatchedEvent {
+            paired_position: self.paired_position.key(),
+            match_id,
+            stay_in_order: self.stay_in_order.key(),
+            breakout_order: self.breakout_order.key(),
+            stay_in_user: self.stay_in_order.owner,
+            breakout_user: self.breakout_order.owner,
+            fill_amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct OrdersMatchedEvent {
+    pub paired_position: Pubkey,
+    pub match_id: u64,
+    pub stay_in_order: Pubkey,
+    pub breakout_order: Pubkey,
+    pub stay_in_user: Pubkey,
+    pub breakout_user: Pubkey,
+    pub fill_amount: u64,
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::state::{
+    grow_account, read_legacy_account, PositionIndex, PositionState, PositionStateV0, PositionStatus, TradingPool,
+    VaultState,
+};
+use crate::error::ErrorCode;
+
+// The owner's vault state and the trading pool must be migrated first, since the
+// position's id and liability are added to them
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct MigratePosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Discriminator and legacy size are checked before it is read
+    #[account(mut, owner = crate::ID)]
+    pub position: UncheckedAccount<'info>,
+
+    // Checked against the position's owner once the position is read
+    #[account(
+        mut,
+        seeds = [b"vault_state", user_vault_state.authority.as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Box<Account<'info, TradingPool>>,
+
+    // Index page the position id is listed on, passed unless the position was already claimed
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PositionIndex::INIT_SPACE,
+        seeds = [
+            b"position_index".as_ref(),
+            user_vault_state.authority.as_ref(),
+            &PositionIndex::page_of(position_id).to_le_bytes()
+        ],
+        bump
+    )]
+    pub position_index: Option<Box<Account<'info, PositionIndex>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigratePosition<'info> {
+    pub fn migrate_position(&mut self, position_id: u64, bumps: &MigratePositionBumps) -> Result<()> {
+        let space = 8 + PositionState::INIT_SPACE;
+        let legacy: PositionStateV0 = read_legacy_account::<PositionState, _>(
+            &self.position,
+            8 + PositionStateV0::INIT_SPACE,
+            space,
+        )?;
+        let migrated = PositionState::from(legacy);
+        require!(migrated.user == self.user_vault_state.authority, ErrorCode::UnauthorizedAccess);
+        require!(migrated.position_id == position_id, ErrorCode::InvalidPositionIndex);
+
+        // Claims take the position's index page, so an unclaimed position must be listed
+        match (migrated.status(), self.position_index.as_mut()) {
+            (PositionStatus::Claimed, None) => {}
+            (PositionStatus::Claimed, Some(_)) | (_, None) => return err!(ErrorCode::InvalidPositionIndex),
+            (_, Some(position_index)) => {
+                position_index.ensure_initialized(
+                    migrated.user,
+                    PositionIndex::page_of(position_id),
+                    bumps.position_index.unwrap_or_default(),
+                );
+                position_index.insert(position_id)?;
+            }
+        }
+
+        // Open positions carry their full liability, settled ones only their known payout
+        match migrated.status() {
+            PositionStatus::Active => {
+                self.trading_pool.add_liability(migrated.amount, migrated.max_payout())?;
+            }
+            PositionStatus::Settled => {
+                let payout = migrated.payout_amount(migrated.payout_percentage);
+                self.trading_pool.add_liability(payout, payout)?;
+            }
+            PositionStatus::Claimed => {}
+        }
+        self.user_vault_state.record_migrated(
+            migrated.position_id,
+            migrated.amount,
+            migrated.max_payout(),
+            migrated.status() != PositionStatus::Claimed,
+        )?;
+
+        grow_account(&self.position, space, &self.payer, &self.system_program)?;
+
+        let mut data = self.position.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(PositionState::DISCRIMINATOR);
+        data[8..].copy_from_slice(bytemuck::bytes_of(&migrated));
+
+        emit!(PositionStateMigratedEvent {
+            position: self.position.key(),
+            version: migrated.version,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PositionStateMigratedEvent {
+    pub position: Pubkey,
+    pub version: u8,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::{read_legacy_account, write_migrated_account, TradingPool, TradingPoolV0};
+
+#[derive(Accounts)]
+pub struct MigrateTradingPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Discriminator and legacy size are checked before it is read
+    #[account(mut, owner = crate::ID)]
+    pub trading_pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateTradingPool<'info> {
+    pub fn migrate_trading_pool(&mut self) -> Result<()> {
+        let space = 8 + TradingPool::INIT_SPACE;
+        let legacy: TradingPoolV0 = read_legacy_account::<TradingPool, _>(
+            &self.trading_pool,
+            8 + TradingPoolV0::INIT_SPACE,
+            space,
+        )?;
+        let migrated = TradingPool::from(legacy);
+
+        write_migrated_account(
+            &self.trading_pool,
+            &migrated,
+            space,
+            &self.payer,
+            &self.system_program,
+        )?;
+
+        emit!(TradingPoolMigratedEvent {
+            trading_pool: self.trading_pool.key(),
+            version: migrated.version,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct TradingPoolMigratedEvent {
+    pub trading_pool: Pubkey,
+    pub version: u8,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::{read_legacy_account, write_migrated_account, VaultState, VaultStateV0};
+
+#[derive(Accounts)]
+pub struct MigrateVaultState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Discriminator and legacy size are checked before it is read
+    #[account(mut, owner = crate::ID)]
+    pub vault_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateVaultState<'info> {
+    pub fn migrate_vault_state(&mut self) -> Result<()> {
+        let space = 8 + VaultState::INIT_SPACE;
+        let legacy: VaultStateV0 = read_legacy_account::<VaultState, _>(
+            &self.vault_state,
+            8 + VaultStateV0::INIT_SPACE,
+            space,
+        )?;
+        let migrated = VaultState::from(legacy);
+
+        write_migrated_account(
+            &self.vault_state,
+            &migrated,
+            space,
+            &self.payer,
+            &self.system_program,
+        )?;
+
+        emit!(VaultStateMigratedEvent {
+            vault_state: self.vault_state.key(),
+            version: migrated.version,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct VaultStateMigratedEvent {
+    pub vault_state: Pubkey,
+    pub version: u8,
+}
@@ -20,11 +20,175 @@ pub use create_position::*;
 pub mod check_position;
 pub use check_position::*;
 
+pub mod create_position_ladder;
+pub use create_position_ladder::*;
+
+pub mod check_position_ladder;
+pub use check_position_ladder::*;
+
 pub mod claim_position;
 pub use claim_position::*;
 
+pub mod tokenize_position;
+pub use tokenize_position::*;
+
+pub mod set_roll_config;
+pub use set_roll_config::*;
+
+pub mod roll_position;
+pub use roll_position::*;
+
+pub mod claim_tokenized_position;
+pub use claim_tokenized_position::*;
+
 
 // <---------------- Pool ----------------------->
 
 pub mod init_trading_pool;
-pub use init_trading_pool::*;
\ No newline at end of file
+pub use init_trading_pool::*;
+
+pub mod fund_pool;
+pub use fund_pool::*;
+
+pub mod withdraw_pool_surplus;
+pub use withdraw_pool_surplus::*;
+
+pub mod init_lp_mint;
+pub use init_lp_mint::*;
+
+pub mod lp_deposit;
+pub use lp_deposit::*;
+
+pub mod lp_withdraw;
+pub use lp_withdraw::*;
+
+pub mod update_pool_config;
+pub use update_pool_config::*;
+
+pub mod update_position_size_limits;
+pub use update_position_size_limits::*;
+
+pub mod pool_stats;
+pub use pool_stats::*;
+
+
+// <---------------- Protocol ----------------------->
+
+pub mod init_protocol_config;
+pub use init_protocol_config::*;
+
+pub mod queue_config_change;
+pub use queue_config_change::*;
+
+pub mod execute_config_change;
+pub use execute_config_change::*;
+
+pub mod cancel_config_change;
+pub use cancel_config_change::*;
+
+pub mod transfer_authority;
+pub use transfer_authority::*;
+
+pub mod withdraw_fees;
+pub use withdraw_fees::*;
+
+pub mod update_role;
+pub use update_role::*;
+
+pub mod set_paused;
+pub use set_paused::*;
+
+
+// <---------------- Referral ----------------------->
+
+pub mod register_referrer;
+pub use register_referrer::*;
+
+pub mod claim_referral_rewards;
+pub use claim_referral_rewards::*;
+
+
+// <---------------- Risk ----------------------->
+
+pub mod init_exposure_book;
+pub use init_exposure_book::*;
+
+pub mod update_exposure_limit;
+pub use update_exposure_limit::*;
+
+pub mod update_user_limits;
+pub use update_user_limits::*;
+
+
+// <---------------- Order Book ----------------------->
+
+pub mod init_order_book;
+pub use init_order_book::*;
+
+pub mod place_order;
+pub use place_order::*;
+
+pub mod cancel_order;
+pub use cancel_order::*;
+
+pub mod match_orders;
+pub use match_orders::*;
+
+pub mod fill_order;
+pub use fill_order::*;
+
+pub mod create_paired_position;
+pub use create_paired_position::*;
+
+pub mod create_position_with_quote;
+pub use create_position_with_quote::*;
+
+pub mod settle_paired_position;
+pub use settle_paired_position::*;
+
+pub mod claim_paired_position;
+pub use claim_paired_position::*;
+
+
+// <---------------- Parimutuel ----------------------->
+
+pub mod init_parimutuel_market;
+pub use init_parimutuel_market::*;
+
+pub mod open_epoch;
+pub use open_epoch::*;
+
+pub mod stake_epoch;
+pub use stake_epoch::*;
+
+pub mod settle_epoch;
+pub use settle_epoch::*;
+
+pub mod claim_epoch;
+pub use claim_epoch::*;
+
+
+// <---------------- Compression ----------------------->
+
+pub mod init_position_tree;
+pub use init_position_tree::*;
+
+pub mod create_compressed_position;
+pub use create_compressed_position::*;
+
+pub mod settle_compressed_position;
+pub use settle_compressed_position::*;
+
+pub mod claim_compressed_position;
+pub use claim_compressed_position::*;
+
+// <---------------- Migrations ----------------------->
+
+pub mod migrate_vault_state;
+pub use migrate_vault_state::*;
+
+pub mod migrate_position;
+pub use migrate_position::*;
+
+pub mod migrate_trading_pool;
+pub use migrate_trading_pool::*;
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{Epoch, EpochStatus, ParimutuelMarket, ProtocolConfig, Role, Roles};
+use crate::error::ErrorCode;
+use crate::constants::MAXIMUM_AGE;
+
+#[derive(Accounts)]
+#[instruction(epoch_index: u64)]
+pub struct OpenEpoch<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [b"parimutuel_market", market.trading_pool.as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, ParimutuelMarket>,
+
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + Epoch::INIT_SPACE,
+        seeds = [b"epoch", market.key().as_ref(), &epoch_index.to_le_bytes()],
+        bump
+    )]
+    pub epoch: Box<Account<'info, Epoch>>,
+
+    // Holds every stake of the epoch until claimed
+    #[account(
+        mut,
+        seeds = [b"epoch_vault", epoch.key().as_ref()],
+        bump
+    )]
+    pub epoch_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenEpoch<'info> {
+    pub fn open_epoch(&mut self, epoch_index: u64, bumps: &OpenEpochBumps) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            epoch_index == self.market.epoch_index_at(clock.unix_timestamp),
+            ErrorCode::InvalidEpoch
+        );
+
+        let price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+
+        let open_price = price_data.price as u64;
+        let (lower_bound, upper_bound) = self.market.band_around(open_price);
+        let start_time = epoch_index as i64 * self.market.epoch_length;
+
+        self.epoch.set_inner(Epoch {
+            version: Epoch::VERSION,
+            market: self.market.key(),
+            epoch_index,
+            start_time,
+            end_time: start_time + self.market.epoch_length,
+            open_price,
+            lower_bound,
+            upper_bound,
+            stay_in_total: 0,
+            breakout_total: 0,
+            status: EpochStatus::Open,
+            winning_side: None,
+            settlement_time: 0,
+            settlement_price: 0,
+            fee: 0,
+            distributable: 0,
+            bump: bumps.epoch,
+            vault_bump: bumps.epoch_vault,
+            reserved: [0; 64],
+        });
+
+        // Keep the vault rent exempt so rounding dust never blocks the last claim
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let shortfall = rent_exempt_minimum.saturating_sub(self.epoch_vault.lamports());
+
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.keeper.to_account_info(),
+                    to: self.epoch_vault.to_account_info(),
+                },
+            );
+
+            transfer(cpi_ctx, shortfall)?;
+        }
+
+        emit!(EpochOpenedEvent {
+            epoch: self.epoch.key(),
+            market: self.market.key(),
+            epoch_index,
+            start_time,
+            open_price,
+            lower_bound,
+            upper_bound,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct EpochOpenedEvent {
+    pub epoch: Pubkey,
+    pub market: Pubkey,
+    pub epoch_index: u64,
+    pub start_time: i64,
+    pub open_price: u64,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{Order, OrderBook, OrderStatus, PositionType, ProtocolConfig, VaultState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book", order_book.trading_pool.as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"order_book_vault", order_book.key().as_ref()],
+        bump = order_book.vault_bump
+    )]
+    pub order_book_vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Order::INIT_SPACE,
+        seeds = [
+            b"order".as_ref(),
+            order_book.key().as_ref(),
+            &order_book.next_order_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+
+    // User's personal vault, collateral is locked from here
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceOrder<'info> {
+    pub fn place_order(
+        &mut self,
+        position_type: PositionType,
+        lower_bound: u64,
+        upper_bound: u64,
+        size: u64,
+        expiry: i64,
+        bumps: &PlaceOrderBumps,
+    ) -> Result<()> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+        require!(lower_bound < upper_bound, ErrorCode::InvalidRange);
+        require!(size >= VaultState::MIN_ORDER_AMOUNT, ErrorCode::AmountTooSmall);
+        require!(
+            self.user_vault.lamports() >= size,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        let created_at = Clock::get()?.unix_timestamp;
+        require!(expiry > created_at, ErrorCode::InvalidExpiry);
+
+        let order_id = self.order_book.take_order_id()?;
+
+        self.order.set_inner(Order {
+            version: Order::VERSION,
+            order_book: self.order_book.key(),
+            order_id,
+            owner: self.user.key(),
+            position_type,
+            lower_bound,
+            upper_bound,
+            size,
+            filled_amount: 0,
+            created_at,
+            expiry,
+            status: OrderStatus::Open,
+            bump: bumps.order,
+            reserved: [0; 64],
+        });
+
+        // Lock collateral from user vault in the order book vault
+        let user_vault_seeds = &[
+            b"vault".as_ref(),
+            self.user_vault_state.to_account_info().key.as_ref(),
+            &[self.user_vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&user_vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.user_vault.to_account_info(),
+                to: self.order_book_vault.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, size)?;
+
+        emit!(OrderPlacedEvent {
+            order: self.order.key(),
+            order_book: self.order_book.key(),
+            order_id,
+            owner: self.user.key(),
+            position_type,
+            lower_bound,
+            upper_bound,
+            size,
+            expiry,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct OrderPlacedEvent {
+    pub order: Pubkey,
+    pub order_book: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub position_type: PositionType,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub size: u64,
+    pub expiry: i64,
+}
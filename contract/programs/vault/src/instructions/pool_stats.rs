@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::TradingPool;
+
+#[derive(Accounts)]
+pub struct GetPoolStats<'info> {
+    #[account(
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+}
+
+impl<'info> GetPoolStats<'info> {
+    pub fn get_pool_stats(&self) -> Result<PoolStats> {
+        let pool = &self.trading_pool;
+
+        Ok(PoolStats {
+            total_pool_amount: pool.total_pool_amount,
+            total_active_amount: pool.total_active_amount,
+            worst_case_liability: pool.worst_case_liability,
+            expected_liability: pool.expected_liability,
+            nav: pool.nav(),
+            free_liquidity: pool.free_liquidity(),
+            utilization_bps: pool.utilization_bps(),
+        })
+    }
+}
+
+// Returned to the caller via return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PoolStats {
+    pub total_pool_amount: u64,
+    pub total_active_amount: u64,
+    pub worst_case_liability: u64,
+    pub expected_liability: u64,
+    pub nav: u64,
+    pub free_liquidity: u64,
+    pub utilization_bps: u64,
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::state::{ConfigChange, ProtocolConfig, QueuedChange};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + QueuedChange::INIT_SPACE,
+        seeds = [
+            b"queued_change".as_ref(),
+            protocol_config.key().as_ref(),
+            &protocol_config.next_change_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub queued_change: Account<'info, QueuedChange>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> QueueConfigChange<'info> {
+    pub fn queue_config_change(
+        &mut self,
+        change: ConfigChange,
+        bumps: &QueueConfigChangeBumps,
+    ) -> Result<()> {
+        change.validate()?;
+
+        let queued_at = Clock::get()?.unix_timestamp;
+        let executable_at = queued_at
+            .checked_add(self.protocol_config.timelock_delay)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let change_id = self.protocol_config.next_change_id;
+
+        self.queued_change.set_inner(QueuedChange {
+            version: QueuedChange::VERSION,
+            change_id,
+            proposer: self.authority.key(),
+            change,
+            queued_at,
+            executable_at,
+            bump: bumps.queued_change,
+            reserved: [0; 64],
+        });
+
+        self.protocol_config.next_change_id = change_id
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(ConfigChangeQueuedEvent {
+            queued_change: self.queued_change.key(),
+            change_id,
+            change,
+            executable_at,
+        });
+
+        Ok(())
+    }
+}
+
+// Tells watchers when the change becomes executable
+#[event]
+pub struct ConfigChangeQueuedEvent {
+    pub queued_change: Pubkey,
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub executable_at: i64,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::Referrer;
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Referrer::INIT_SPACE,
+        seeds = [b"referrer", authority.key().as_ref()],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterReferrer<'info> {
+    pub fn register_referrer(&mut self, bumps: &RegisterReferrerBumps) -> Result<()> {
+        self.referrer.set_inner(Referrer {
+            version: Referrer::VERSION,
+            authority: self.authority.key(),
+            referred_positions: 0,
+            total_volume: 0,
+            total_earnings: 0,
+            claimable: 0,
+            bump: bumps.referrer,
+            reserved: [0; 64],
+        });
+
+        emit!(ReferrerRegisteredEvent {
+            referrer: self.referrer.key(),
+            authority: self.authority.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ReferrerRegisteredEvent {
+    pub referrer: Pubkey,
+    pub authority: Pubkey,
+}
@@ -0,0 +1,396 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{
+    remove_from_index, ExposureBook, PositionIndex, PositionState, PositionStatus, ProtocolConfig, Role, Roles,
+    TradingPool, UserStats, VaultState,
+};
+use crate::instructions::{create_position_account, settle_pool_position, PositionSettledEvent};
+use crate::error::ErrorCode;
+use crate::constants::MAXIMUM_AGE;
+
+#[derive(Accounts)]
+pub struct RollPosition<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// CHECK: Only used for seed and validation, and refunded the rent of an emptied index page
+    #[account(mut)]
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position".as_ref(),
+            user.key().as_ref(),
+            &position.load()?.position_id.to_le_bytes()
+        ],
+        bump = position.load()?.bump,
+        constraint = position.load()?.user == user.key(),
+        constraint = position.load()?.status() != PositionStatus::Claimed @ ErrorCode::AlreadyClaimed,
+        constraint = position.load()?.position_mint().is_none() @ ErrorCode::PositionTokenized,
+    )]
+    pub position: AccountLoader<'info, PositionState>,
+
+    /// CHECK: Created in the handler, with its rent paid from the user's vault
+    #[account(
+        mut,
+        seeds = [
+            b"position".as_ref(),
+            user.key().as_ref(),
+            &user_vault_state.next_position_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub next_position: UncheckedAccount<'info>,
+
+    // Receives the payout and funds the next position and its rent
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Box<Account<'info, TradingPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the winnings and opening fees
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // User's running position stats
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    // Index page the rolled position id is removed from
+    #[account(
+        mut,
+        seeds = [
+            b"position_index".as_ref(),
+            user.key().as_ref(),
+            &PositionIndex::page_of(position.load()?.position_id).to_le_bytes()
+        ],
+        bump = position_index.bump
+    )]
+    pub position_index: Box<Account<'info, PositionIndex>>,
+
+    // Index page for the next position id, only passed when it differs from position_index
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + PositionIndex::INIT_SPACE,
+        seeds = [
+            b"position_index".as_ref(),
+            user.key().as_ref(),
+            &PositionIndex::page_of(user_vault_state.next_position_id).to_le_bytes()
+        ],
+        bump
+    )]
+    pub next_position_index: Option<Box<Account<'info, PositionIndex>>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RollPosition<'info> {
+    pub fn roll_position(&mut self, bumps: &RollPositionBumps) -> Result<u64> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+
+        let mut position = self.position.load_mut()?;
+        let roll_config = position.roll_config().ok_or(ErrorCode::NoRollsRemaining)?;
+        require!(roll_config.rolls_remaining > 0, ErrorCode::NoRollsRemaining);
+
+        let clock = Clock::get()?;
+        let price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+        let current_price = price_data.price as u64;
+        let current_time = clock.unix_timestamp;
+
+        self.user_stats.ensure_initialized(position.user, bumps.user_stats);
+
+        // A position already settled by check_position is rolled on its recorded outcome,
+        // otherwise it is settled here first
+        let payout_percentage = match position.settlement_data() {
+            Some(settlement_data) => settlement_data.payout_percentage,
+            None => {
+                let payout_percentage = settle_pool_position(
+                    &mut position,
+                    &mut self.trading_pool,
+                    &mut self.exposure_book,
+                    &mut self.user_stats,
+                    current_time,
+                    current_price,
+                )?.ok_or(ErrorCode::PositionNotDue)?;
+
+                emit!(PositionSettledEvent {
+                    position: self.position.key(),
+                    user: position.user,
+                    settlement_time: current_time,
+                    settlement_price: current_price,
+                    payout_percentage,
+                    is_winner: payout_percentage > 100,
+                });
+
+                payout_percentage
+            }
+        };
+
+        // Claim the settled position into the user's vault, as claim_position does
+        let payout_amount = position.payout_amount(payout_percentage);
+        let winnings_fee = self.protocol_config.winnings_fee(position.amount, payout_amount);
+        let user_payout = payout_amount.checked_sub(winnings_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        self.trading_pool.record_claim(position.amount, payout_amount)?;
+        self.user_vault_state.record_close(position.amount, position.max_payout());
+        position.claim()?;
+
+        self.user_stats.record_claim(position.amount, user_payout, winnings_fee)?;
+
+        require!(
+            self.trading_pool_vault.lamports() >= payout_amount,
+            ErrorCode::InsufficientPoolBalance
+        );
+
+        let pool_vault_seeds = &[
+            b"trading_pool_vault",
+            self.trading_pool.to_account_info().key.as_ref(),
+            &[self.trading_pool.vault_bump],
+        ];
+        let pool_signer_seeds = &[&pool_vault_seeds[..]];
+
+        if user_payout > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.trading_pool_vault.to_account_info(),
+                    to: self.user_vault.to_account_info(),
+                },
+                pool_signer_seeds,
+            );
+
+            transfer(cpi_ctx, user_payout)?;
+        }
+
+        if winnings_fee > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.trading_pool_vault.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+                pool_signer_seeds,
+            );
+
+            transfer(cpi_ctx, winnings_fee)?;
+            self.protocol_config.record_fee(winnings_fee)?;
+        }
+
+        // Reopen on the same side with the band recentered on the current price.
+        // The vault pays for the stake and the next position's rent
+        let amount = roll_config.reinvest_amount(position.amount, user_payout);
+        let position_space = 8 + PositionState::INIT_SPACE;
+        let position_rent = Rent::get()?.minimum_balance(position_space);
+        require!(
+            self.user_vault.lamports() >= amount.checked_add(position_rent).ok_or(ErrorCode::MathOverflow)?,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        let (lower_bound, upper_bound) = roll_config.band_around(current_price);
+        require!(lower_bound < upper_bound, ErrorCode::InvalidRange);
+
+        let opening_fee = self.protocol_config.opening_fee(amount);
+        let stake = amount.checked_sub(opening_fee).ok_or(ErrorCode::MathOverflow)?;
+        self.trading_pool.check_position_size(stake)?;
+        let position_id = self.user_vault_state.take_position_id()?;
+        let position_type = position.position_type();
+
+        let user_key = self.user.key();
+        let position_id_bytes = position_id.to_le_bytes();
+        let next_position_seeds = &[
+            b"position".as_ref(),
+            user_key.as_ref(),
+            &position_id_bytes,
+            &[bumps.next_position],
+        ];
+        let user_vault_state_key = self.user_vault_state.key();
+        let user_vault_seeds = &[
+            b"vault".as_ref(),
+            user_vault_state_key.as_ref(),
+            &[self.user_vault_state.vault_bump],
+        ];
+
+        create_position_account(
+            &self.next_position.to_account_info(),
+            &self.user_vault.to_account_info(),
+            &self.system_program.to_account_info(),
+            position_rent,
+            position_space,
+            &[&next_position_seeds[..], &user_vault_seeds[..]],
+        )?;
+
+        let mut next_position: PositionState = bytemuck::Zeroable::zeroed();
+        next_position.initialize(
+            position.user,
+            position_type,
+            lower_bound,
+            upper_bound,
+            current_time,
+            self.protocol_config.position_duration,
+            position_id,
+            stake,
+            bumps.next_position,
+        )?;
+        next_position.set_roll_config(roll_config.next());
+        let next_max_payout = next_position.max_payout();
+
+        let mut data = self.next_position.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(PositionState::DISCRIMINATOR);
+        data[8..].copy_from_slice(bytemuck::bytes_of(&next_position));
+        drop(data);
+
+        let next_page = PositionIndex::page_of(position_id);
+        if PositionIndex::page_of(position.position_id) == next_page {
+            // Passing the same page twice would let one copy overwrite the other
+            require!(self.next_position_index.is_none(), ErrorCode::InvalidPositionIndex);
+            self.position_index.remove(position.position_id)?;
+            self.position_index.insert(position_id)?;
+        } else {
+            let next_position_index = self.next_position_index.as_mut().ok_or(ErrorCode::InvalidPositionIndex)?;
+            next_position_index.ensure_initialized(
+                position.user,
+                next_page,
+                bumps.next_position_index.unwrap_or_default(),
+            );
+            next_position_index.insert(position_id)?;
+            remove_from_index(&mut self.position_index, position.position_id, &self.user.to_account_info())?;
+        }
+        self.user_stats.record_open(stake, opening_fee)?;
+
+        self.user_vault_state.record_open(stake, next_max_payout, &self.trading_pool)?;
+        self.trading_pool.check_solvency(stake, next_max_payout)?;
+        self.exposure_book.add_exposure(position_type, lower_bound, upper_bound, stake)?;
+
+        let vault_signer_seeds = &[&user_vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.user_vault.to_account_info(),
+                to: self.trading_pool_vault.to_account_info(),
+            },
+            vault_signer_seeds,
+        );
+
+        transfer(cpi_ctx, stake)?;
+
+        if opening_fee > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user_vault.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+                vault_signer_seeds,
+            );
+
+            transfer(cpi_ctx, opening_fee)?;
+            self.protocol_config.record_fee(opening_fee)?;
+        }
+
+        self.trading_pool.total_active_amount = self.trading_pool.total_active_amount.checked_add(stake)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount.checked_add(stake)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.trading_pool.add_liability(stake, next_max_payout)?;
+
+        emit!(PositionRolledEvent {
+            position: self.position.key(),
+            next_position: self.next_position.key(),
+            user: position.user,
+            payout_amount: user_payout,
+            winnings_fee,
+            position_id,
+            lower_bound,
+            upper_bound,
+            amount: stake,
+            opening_fee,
+            rolls_remaining: roll_config.rolls_remaining - 1,
+        });
+
+        Ok(position_id)
+    }
+}
+
+#[event]
+pub struct PositionRolledEvent {
+    pub position: Pubkey,
+    pub next_position: Pubkey,
+    pub user: Pubkey,
+    pub payout_amount: u64,
+    pub winnings_fee: u64,
+    pub position_id: u64,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub amount: u64,
+    pub opening_fee: u64,
+    pub rolls_remaining: u8,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, Role, Roles};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub pauser: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Pauser, &pauser.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+impl<'info> SetPaused<'info> {
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        self.protocol_config.paused = paused;
+
+        emit!(PausedUpdatedEvent {
+            protocol_config: self.protocol_config.key(),
+            paused,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PausedUpdatedEvent {
+    pub protocol_config: Pubkey,
+    pub paused: bool,
+}
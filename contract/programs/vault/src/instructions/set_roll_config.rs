@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::{PositionState, PositionStatus, RollConfig};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetRollConfig<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position".as_ref(),
+            user.key().as_ref(),
+            &position.load()?.position_id.to_le_bytes()
+        ],
+        bump = position.load()?.bump,
+        constraint = position.load()?.user == user.key(),
+        constraint = position.load()?.status() == PositionStatus::Active @ ErrorCode::PositionAlreadySettled,
+        constraint = position.load()?.position_mint().is_none() @ ErrorCode::PositionTokenized,
+    )]
+    pub position: AccountLoader<'info, PositionState>,
+}
+
+impl<'info> SetRollConfig<'info> {
+    pub fn set_roll_config(&mut self, roll_config: Option<RollConfig>) -> Result<()> {
+        if let Some(roll_config) = roll_config.as_ref() {
+            roll_config.validate()?;
+        }
+
+        self.position.load_mut()?.set_roll_config(roll_config);
+
+        emit!(RollConfigUpdatedEvent {
+            position: self.position.key(),
+            user: self.user.key(),
+            roll_config,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct RollConfigUpdatedEvent {
+    pub position: Pubkey,
+    pub user: Pubkey,
+    pub roll_config: Option<RollConfig>,
+}
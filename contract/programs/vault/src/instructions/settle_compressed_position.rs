@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{
+    CompressedPosition, ExposureBook, PositionTree, ProtocolConfig, Role, Roles, TradingPool, UserStats,
+};
+use crate::instructions::settle_pool_position;
+use crate::error::ErrorCode;
+use crate::constants::MAXIMUM_AGE;
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], position: CompressedPosition)]
+pub struct SettleCompressedPosition<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = position_tree.load()?.trading_pool == trading_pool.key() @ ErrorCode::InvalidPositionTree,
+    )]
+    pub position_tree: AccountLoader<'info, PositionTree>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"exposure_book", trading_pool.key().as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    // Position owner's stats, which record the outcome
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", position.user.as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleCompressedPosition<'info> {
+    // `position` is the current leaf, proven against `root` by `proof`
+    pub fn settle_compressed_position(
+        &mut self,
+        root: [u8; 32],
+        position: CompressedPosition,
+        proof: Vec<[u8; 32]>,
+        bumps: &SettleCompressedPositionBumps,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+
+        let price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+
+        let current_price = price_data.price as u64;
+        let current_time = clock.unix_timestamp;
+
+        self.user_stats.ensure_initialized(position.user, bumps.user_stats);
+
+        let mut position_state = position.to_position_state()?;
+        if let Some(payout_percentage) = settle_pool_position(
+            &mut position_state,
+            &mut self.trading_pool,
+            &mut self.exposure_book,
+            &mut self.user_stats,
+            current_time,
+            current_price,
+        )? {
+            let mut settled = position;
+            settled.update_from(&position_state);
+
+            let mut position_tree = self.position_tree.load_mut()?;
+            position_tree.replace_leaf(root, &proof, position.leaf_index, position.leaf()?, settled.leaf()?)?;
+
+            emit!(CompressedPositionSettledEvent {
+                position_tree: self.position_tree.key(),
+                position: settled,
+                root: position_tree.root(),
+                payout_percentage,
+                is_winner: payout_percentage > 100,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct CompressedPositionSettledEvent {
+    pub position_tree: Pubkey,
+    pub position: CompressedPosition,
+    pub root: [u8; 32],
+    pub payout_percentage: u8,
+    pub is_winner: bool,
+}
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{Epoch, EpochStatus, ParimutuelMarket, PositionType, ProtocolConfig, Role, Roles};
+use crate::error::ErrorCode;
+use crate::constants::MAXIMUM_AGE;
+
+#[derive(Accounts)]
+pub struct SettleEpoch<'info> {
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [b"parimutuel_market", market.trading_pool.as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, ParimutuelMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch", market.key().as_ref(), &epoch.epoch_index.to_le_bytes()],
+        bump = epoch.bump,
+    )]
+    pub epoch: Box<Account<'info, Epoch>>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch_vault", epoch.key().as_ref()],
+        bump = epoch.vault_bump
+    )]
+    pub epoch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    // Treasury collecting the fee on the losing side
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleEpoch<'info> {
+    pub fn settle_epoch(&mut self) -> Result<()> {
+        if self.epoch.status != EpochStatus::Open {
+            return Ok(());
+        }
+
+        let clock = Clock::get()?;
+
+        let price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+
+        let current_price = price_data.price as u64;
+        let publish_time = price_data.publish_time;
+        require!(publish_time >= self.epoch.start_time, ErrorCode::PriceOutsideEpoch);
+
+        // Breakout wins on a price published outside the band before the epoch ends,
+        // StayIn on a price published once it has ended. Nothing records a breakout
+        // on-chain in between, so Breakout stakers rely on keepers submitting such a
+        // price while it is fresh; a breakout no keeper reports settles as StayIn
+        let winning_side = if self.epoch.is_outside_range(current_price) && publish_time <= self.epoch.end_time {
+            PositionType::Breakout
+        } else if publish_time >= self.epoch.end_time {
+            PositionType::StayIn
+        } else {
+            return Ok(());
+        };
+
+        self.epoch.settle(
+            winning_side,
+            publish_time,
+            current_price,
+            self.protocol_config.winnings_fee_bps,
+        )?;
+
+        let fee = self.epoch.fee;
+        if fee > 0 {
+            let epoch_key = self.epoch.key();
+            let epoch_vault_seeds = &[
+                b"epoch_vault".as_ref(),
+                epoch_key.as_ref(),
+                &[self.epoch.vault_bump],
+            ];
+            let signer_seeds = &[&epoch_vault_seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.epoch_vault.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(cpi_ctx, fee)?;
+            self.protocol_config.record_fee(fee)?;
+        }
+
+        emit!(EpochSettledEvent {
+            epoch: self.epoch.key(),
+            epoch_index: self.epoch.epoch_index,
+            settlement_time: publish_time,
+            settlement_price: current_price,
+            winning_side: self.epoch.winning_side,
+            fee,
+            distributable: self.epoch.distributable,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct EpochSettledEvent {
+    pub epoch: Pubkey,
+    pub epoch_index: u64,
+    pub settlement_time: i64,
+    pub settlement_price: u64,
+    pub winning_side: Option<PositionType>,
+    pub fee: u64,
+    pub distributable: u64,
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{OrderBook, PairedPosition, PositionStatus, ProtocolConfig, Role, Roles};
+use crate::error::ErrorCode;
+use crate::constants::MAXIMUM_AGE;
+
+#[derive(Accounts)]
+pub struct SettlePairedPosition<'info> {
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [b"order_book", order_book.trading_pool.as_ref()],
+        bump = order_book.bump,
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"paired_position".as_ref(),
+            order_book.key().as_ref(),
+            &paired_position.match_id.to_le_bytes()
+        ],
+        bump = paired_position.bump,
+    )]
+    pub paired_position: Account<'info, PairedPosition>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Keeper, &keeper.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>
+}
+
+impl<'info> SettlePairedPosition<'info> {
+    pub fn settle_paired_position(&mut self) -> Result<()> {
+        if self.paired_position.status != PositionStatus::Active {
+            return Ok(());
+        }
+
+        let clock = Clock::get()?;
+
+        let price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+
+        let current_price = price_data.price as u64;
+        let current_time = clock.unix_timestamp;
+
+        if self.paired_position.settle(current_time, current_price)? {
+            emit!(PairedPositionSettledEvent {
+                paired_position: self.paired_position.key(),
+                settlement_time: current_time,
+                settlement_price: current_price,
+                stay_in_payout: self.paired_position.stay_in_payout,
+                breakout_payout: self.paired_position.breakout_payout,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PairedPositionSettledEvent {
+    pub paired_position: Pubkey,
+    pub settlement_time: i64,
+    pub settlement_price: u64,
+    pub stay_in_payout: u64,
+    pub breakout_payout: u64,
+}
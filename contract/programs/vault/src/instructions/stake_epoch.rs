@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use crate::state::{Epoch, EpochStake, EpochStatus, ParimutuelMarket, PositionType, ProtocolConfig, VaultState};
+use crate::error::ErrorCode;
+use crate::constants::MAXIMUM_AGE;
+
+#[derive(Accounts)]
+pub struct StakeEpoch<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"parimutuel_market", market.trading_pool.as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, ParimutuelMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch", market.key().as_ref(), &epoch.epoch_index.to_le_bytes()],
+        bump = epoch.bump,
+    )]
+    pub epoch: Box<Account<'info, Epoch>>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch_vault", epoch.key().as_ref()],
+        bump = epoch.vault_bump
+    )]
+    pub epoch_vault: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + EpochStake::INIT_SPACE,
+        seeds = [b"epoch_stake", epoch.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub epoch_stake: Account<'info, EpochStake>,
+
+    // User's personal vault, the stake is taken from here
+    #[account(
+        mut,
+        seeds = [b"vault", user_vault_state.key().as_ref()],
+        bump = user_vault_state.vault_bump,
+    )]
+    pub user_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault_state", user.key().as_ref()],
+        bump = user_vault_state.state_bump
+    )]
+    pub user_vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        owner = pyth_solana_receiver_sdk::ID,
+        constraint = price_update.verification_level == VerificationLevel::Full,
+    )]
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeEpoch<'info> {
+    pub fn stake_epoch(
+        &mut self,
+        position_type: PositionType,
+        amount: u64,
+        bumps: &StakeEpochBumps,
+    ) -> Result<()> {
+        require!(!self.protocol_config.paused, ErrorCode::ProgramPaused);
+        require!(amount >= VaultState::MIN_ORDER_AMOUNT, ErrorCode::AmountTooSmall);
+        require!(
+            self.user_vault.lamports() >= amount,
+            ErrorCode::InsufficientVaultBalance
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            self.epoch.status == EpochStatus::Open
+                && clock.unix_timestamp < self.epoch.start_time + self.market.stake_window,
+            ErrorCode::StakingWindowClosed
+        );
+
+        // Once the price has left the band the outcome is known, even if no
+        // keeper has settled yet
+        let price_data = self.price_update.get_price_no_older_than(
+            &clock,
+            MAXIMUM_AGE,
+            &self.protocol_config.price_feed_id,
+        ).map_err(|_| error!(ErrorCode::StalePriceFeed))?;
+        require!(
+            !self.epoch.is_outside_range(price_data.price as u64),
+            ErrorCode::EpochBandBroken
+        );
+
+        if self.epoch_stake.user == Pubkey::default() {
+            self.epoch_stake.set_inner(EpochStake {
+                version: EpochStake::VERSION,
+                epoch: self.epoch.key(),
+                user: self.user.key(),
+                stay_in_amount: 0,
+                breakout_amount: 0,
+                bump: bumps.epoch_stake,
+                reserved: [0; 64],
+            });
+        }
+
+        self.epoch_stake.add(position_type, amount)?;
+        self.epoch.add_stake(position_type, amount)?;
+
+        // Transfer stake from user vault to the epoch vault
+        let user_vault_seeds = &[
+            b"vault".as_ref(),
+            self.user_vault_state.to_account_info().key.as_ref(),
+            &[self.user_vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&user_vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.user_vault.to_account_info(),
+                to: self.epoch_vault.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        emit!(EpochStakedEvent {
+            epoch: self.epoch.key(),
+            user: self.user.key(),
+            position_type,
+            amount,
+            stay_in_total: self.epoch.stay_in_total,
+            breakout_total: self.epoch.breakout_total,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct EpochStakedEvent {
+    pub epoch: Pubkey,
+    pub user: Pubkey,
+    pub position_type: PositionType,
+    pub amount: u64,
+    pub stay_in_total: u64,
+    pub breakout_total: u64,
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{mint_to, set_authority, Mint, MintTo, SetAuthority, Token, TokenAccount};
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use crate::state::{PositionState, PositionStatus};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct TokenizePosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"position".as_ref(),
+            user.key().as_ref(),
+            &position.load()?.position_id.to_le_bytes()
+        ],
+        bump = position.load()?.bump,
+        constraint = position.load()?.user == user.key(),
+        constraint = position.load()?.status() != PositionStatus::Claimed @ ErrorCode::AlreadyClaimed,
+        constraint = position.load()?.position_mint().is_none() @ ErrorCode::PositionAlreadyTokenized,
+    )]
+    pub position: AccountLoader<'info, PositionState>,
+
+    // One token per position, the position PDA mints it and then gives up the authority
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"position_mint", position.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = position,
+    )]
+    pub position_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = position_mint,
+        associated_token::authority = user,
+    )]
+    pub user_position_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TokenizePosition<'info> {
+    pub fn tokenize_position(&mut self) -> Result<()> {
+        mint_position_token(
+            &self.position,
+            &self.position_mint.to_account_info(),
+            &self.user_position_token.to_account_info(),
+            &self.token_program.to_account_info(),
+        )
+    }
+}
+
+// Mint the position's single token and remove the mint authority, so the
+// supply stays at one. The position must not be borrowed by the caller
+pub fn mint_position_token<'info>(
+    position: &AccountLoader<'info, PositionState>,
+    position_mint: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    // Copied out so the position data is not borrowed during the CPIs
+    let (user, position_id, bump) = {
+        let position = position.load()?;
+        (position.user, position.position_id.to_le_bytes(), position.bump)
+    };
+    let position_seeds = &[
+        b"position".as_ref(),
+        user.as_ref(),
+        &position_id,
+        &[bump],
+    ];
+    let signer_seeds = &[&position_seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.clone(),
+        MintTo {
+            mint: position_mint.clone(),
+            to: token_account.clone(),
+            authority: position.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    mint_to(cpi_ctx, 1)?;
+
+    // Fix the supply at one
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.clone(),
+        SetAuthority {
+            current_authority: position.to_account_info(),
+            account_or_mint: position_mint.clone(),
+        },
+        signer_seeds,
+    );
+
+    set_authority(cpi_ctx, AuthorityType::MintTokens, None)?;
+
+    position.load_mut()?.position_mint = position_mint.key();
+
+    emit!(PositionTokenizedEvent {
+        position: position.key(),
+        user,
+        position_mint: position_mint.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PositionTokenizedEvent {
+    pub position: Pubkey,
+    pub user: Pubkey,
+    pub position_mint: Pubkey,
+}
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, TradingPool};
+use crate::error::ErrorCode;
+
+// <---------------- Trading Pool ----------------------->
+
+#[derive(Accounts)]
+pub struct ProposePoolAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+}
+
+impl<'info> ProposePoolAuthority<'info> {
+    pub fn propose_pool_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+        self.trading_pool.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposedEvent {
+            account: self.trading_pool.key(),
+            current_authority: self.authority.key(),
+            pending_authority: new_authority,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptPoolAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+        constraint = trading_pool.pending_authority == Some(new_authority.key()) @ ErrorCode::NoPendingAuthority,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+}
+
+impl<'info> AcceptPoolAuthority<'info> {
+    pub fn accept_pool_authority(&mut self) -> Result<()> {
+        let previous_authority = self.trading_pool.authority;
+
+        self.trading_pool.authority = self.new_authority.key();
+        self.trading_pool.pending_authority = None;
+
+        emit!(AuthorityTransferredEvent {
+            account: self.trading_pool.key(),
+            previous_authority,
+            new_authority: self.new_authority.key(),
+        });
+
+        Ok(())
+    }
+}
+
+// <---------------- Protocol Config ----------------------->
+
+#[derive(Accounts)]
+pub struct ProposeProtocolAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+impl<'info> ProposeProtocolAuthority<'info> {
+    pub fn propose_protocol_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+        self.protocol_config.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposedEvent {
+            account: self.protocol_config.key(),
+            current_authority: self.authority.key(),
+            pending_authority: new_authority,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptProtocolAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        constraint = protocol_config.pending_authority == Some(new_authority.key()) @ ErrorCode::NoPendingAuthority,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+impl<'info> AcceptProtocolAuthority<'info> {
+    pub fn accept_protocol_authority(&mut self) -> Result<()> {
+        let previous_authority = self.protocol_config.authority;
+
+        self.protocol_config.authority = self.new_authority.key();
+        self.protocol_config.pending_authority = None;
+
+        emit!(AuthorityTransferredEvent {
+            account: self.protocol_config.key(),
+            previous_authority,
+            new_authority: self.new_authority.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct AuthorityTransferProposedEvent {
+    pub account: Pubkey,
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferredEvent {
+    pub account: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
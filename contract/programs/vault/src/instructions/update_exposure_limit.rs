@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::{ExposureBook, ProtocolConfig, Role, Roles};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct UpdateExposureLimit<'info> {
+    pub risk_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"exposure_book", exposure_book.trading_pool.as_ref()],
+        bump = exposure_book.bump,
+    )]
+    pub exposure_book: Box<Account<'info, ExposureBook>>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::RiskManager, &risk_manager.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+impl<'info> UpdateExposureLimit<'info> {
+    pub fn update_exposure_limit(&mut self, max_net_exposure: u64) -> Result<()> {
+        self.exposure_book.max_net_exposure = max_net_exposure;
+
+        emit!(ExposureLimitUpdatedEvent {
+            exposure_book: self.exposure_book.key(),
+            max_net_exposure,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ExposureLimitUpdatedEvent {
+    pub exposure_book: Pubkey,
+    pub max_net_exposure: u64,
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, Role, Roles, TradingPool};
+use crate::error::ErrorCode;
+use crate::constants::BPS_DENOMINATOR;
+
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    pub risk_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::RiskManager, &risk_manager.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+impl<'info> UpdatePoolConfig<'info> {
+    pub fn update_pool_config(&mut self, max_utilization_bps: u16) -> Result<()> {
+        require!(
+            max_utilization_bps > 0 && max_utilization_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidPoolConfig
+        );
+
+        self.trading_pool.max_utilization_bps = max_utilization_bps;
+
+        emit!(PoolConfigUpdatedEvent {
+            trading_pool: self.trading_pool.key(),
+            max_utilization_bps,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PoolConfigUpdatedEvent {
+    pub trading_pool: Pubkey,
+    pub max_utilization_bps: u16,
+}
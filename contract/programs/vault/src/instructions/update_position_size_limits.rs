@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, Role, Roles, TradingPool, VaultState};
+use crate::error::ErrorCode;
+use crate::constants::BPS_DENOMINATOR;
+
+#[derive(Accounts)]
+pub struct UpdatePositionSizeLimits<'info> {
+    pub risk_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::RiskManager, &risk_manager.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+impl<'info> UpdatePositionSizeLimits<'info> {
+    pub fn update_position_size_limits(
+        &mut self,
+        min_position_size: u64,
+        max_position_size: u64,
+        max_position_liquidity_bps: u16,
+    ) -> Result<()> {
+        require!(
+            min_position_size >= VaultState::MIN_ORDER_AMOUNT,
+            ErrorCode::InvalidPoolConfig
+        );
+        require!(
+            max_position_size == 0 || max_position_size >= min_position_size,
+            ErrorCode::InvalidPoolConfig
+        );
+        require!(
+            max_position_liquidity_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidPoolConfig
+        );
+
+        self.trading_pool.min_position_size = min_position_size;
+        self.trading_pool.max_position_size = max_position_size;
+        self.trading_pool.max_position_liquidity_bps = max_position_liquidity_bps;
+
+        emit!(PositionSizeLimitsUpdatedEvent {
+            trading_pool: self.trading_pool.key(),
+            min_position_size,
+            max_position_size,
+            max_position_liquidity_bps,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PositionSizeLimitsUpdatedEvent {
+    pub trading_pool: Pubkey,
+    pub min_position_size: u64,
+    pub max_position_size: u64,
+    pub max_position_liquidity_bps: u16,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, Role, Roles};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+impl<'info> UpdateRole<'info> {
+    pub fn grant_role(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        self.roles.grant(role, member)?;
+
+        emit!(RoleUpdatedEvent {
+            role,
+            member,
+            granted: true,
+        });
+
+        Ok(())
+    }
+
+    pub fn revoke_role(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        self.roles.revoke(role, member)?;
+
+        emit!(RoleUpdatedEvent {
+            role,
+            member,
+            granted: false,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct RoleUpdatedEvent {
+    pub role: Role,
+    pub member: Pubkey,
+    pub granted: bool,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, Role, Roles, TradingPool};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct UpdateUserLimits<'info> {
+    pub risk_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::RiskManager, &risk_manager.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+impl<'info> UpdateUserLimits<'info> {
+    pub fn update_user_limits(
+        &mut self,
+        max_open_positions: u32,
+        max_notional: u64,
+        max_payout: u64,
+    ) -> Result<()> {
+        self.trading_pool.max_user_open_positions = max_open_positions;
+        self.trading_pool.max_user_notional = max_notional;
+        self.trading_pool.max_user_payout = max_payout;
+
+        emit!(UserLimitsUpdatedEvent {
+            trading_pool: self.trading_pool.key(),
+            max_open_positions,
+            max_notional,
+            max_payout,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct UserLimitsUpdatedEvent {
+    pub trading_pool: Pubkey,
+    pub max_open_positions: u32,
+    pub max_notional: u64,
+    pub max_payout: u64,
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{ProtocolConfig, Role, Roles};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub fee_collector: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"roles", protocol_config.key().as_ref()],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::FeeCollector, &fee_collector.key())? @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury", protocol_config.key().as_ref()],
+        bump = protocol_config.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Any account can receive the withdrawn fees
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawFees<'info> {
+    pub fn withdraw_fees(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::AmountTooSmall);
+
+        // The rent-exempt minimum and unclaimed referral rebates stay in the treasury
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let available = self.treasury.lamports()
+            .saturating_sub(rent_exempt_minimum)
+            .saturating_sub(self.protocol_config.total_referral_owed);
+        require!(amount <= available, ErrorCode::InsufficientTreasuryBalance);
+
+        let treasury_seeds = &[
+            b"treasury".as_ref(),
+            self.protocol_config.to_account_info().key.as_ref(),
+            &[self.protocol_config.treasury_bump],
+        ];
+        let signer_seeds = &[&treasury_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.treasury.to_account_info(),
+                to: self.recipient.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        self.protocol_config.total_fees_withdrawn = self.protocol_config.total_fees_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(FeesWithdrawnEvent {
+            protocol_config: self.protocol_config.key(),
+            recipient: self.recipient.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct FeesWithdrawnEvent {
+    pub protocol_config: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
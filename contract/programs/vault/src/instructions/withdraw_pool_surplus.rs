@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::Mint;
+use crate::state::{ProtocolConfig, TradingPool};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct WithdrawPoolSurplus<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // House liquidity is managed by the protocol authority
+    #[account(
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAccess,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool"],
+        bump = trading_pool.bump,
+    )]
+    pub trading_pool: Account<'info, TradingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"trading_pool_vault", trading_pool.key().as_ref()],
+        bump = trading_pool.vault_bump
+    )]
+    pub trading_pool_vault: SystemAccount<'info>,
+
+    /// CHECK: LP mint PDA, may not be initialized yet. Deserialized in the handler
+    #[account(
+        seeds = [b"lp_mint", trading_pool.key().as_ref()],
+        bump
+    )]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawPoolSurplus<'info> {
+    pub fn withdraw_pool_surplus(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::AmountTooSmall);
+
+        // Once LPs hold shares the surplus belongs to them, the admin exits via lp_withdraw.
+        // The locked minimum shares keep the supply above zero for good, so this only
+        // returns house liquidity seeded before the first LP deposit
+        if !self.lp_mint.data_is_empty() {
+            let lp_mint = Mint::try_deserialize(&mut &self.lp_mint.try_borrow_data()?[..])?;
+            require!(lp_mint.supply == 0, ErrorCode::LpSharesOutstanding);
+        }
+
+        // Funds backing open positions' max payouts and the vault's rent stay in the pool
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let withdrawable = self.trading_pool_vault.lamports()
+            .saturating_sub(rent_exempt_minimum)
+            .min(self.trading_pool.free_liquidity());
+        require!(amount <= withdrawable, ErrorCode::InsufficientPoolLiquidity);
+
+        let pool_vault_seeds = &[
+            b"trading_pool_vault",
+            self.trading_pool.to_account_info().key.as_ref(),
+            &[self.trading_pool.vault_bump],
+        ];
+        let signer_seeds = &[&pool_vault_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.trading_pool_vault.to_account_info(),
+                to: self.authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)?;
+
+        self.trading_pool.total_pool_amount = self.trading_pool.total_pool_amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(PoolSurplusWithdrawnEvent {
+            trading_pool: self.trading_pool.key(),
+            authority: self.authority.key(),
+            amount,
+            total_pool_amount: self.trading_pool.total_pool_amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct PoolSurplusWithdrawnEvent {
+    pub trading_pool: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_pool_amount: u64,
+}
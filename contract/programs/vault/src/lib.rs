@@ -44,24 +44,268 @@ pub mod vault {
         Ok(())
     }
 
+    pub fn update_pool_config(ctx: Context<UpdatePoolConfig>, max_utilization_bps: u16) -> Result<()> {
+        ctx.accounts.update_pool_config(max_utilization_bps)?;
+        Ok(())
+    }
+
+    pub fn update_position_size_limits(
+        ctx: Context<UpdatePositionSizeLimits>,
+        min_position_size: u64,
+        max_position_size: u64,
+        max_position_liquidity_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.update_position_size_limits(min_position_size, max_position_size, max_position_liquidity_bps)?;
+        Ok(())
+    }
+
+    pub fn propose_pool_authority(ctx: Context<ProposePoolAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.propose_pool_authority(new_authority)?;
+        Ok(())
+    }
+
+    pub fn accept_pool_authority(ctx: Context<AcceptPoolAuthority>) -> Result<()> {
+        ctx.accounts.accept_pool_authority()?;
+        Ok(())
+    }
+
+    pub fn fund_pool(ctx: Context<FundPool>, amount: u64) -> Result<()> {
+        ctx.accounts.fund_pool(amount)?;
+        Ok(())
+    }
+
+    pub fn withdraw_pool_surplus(ctx: Context<WithdrawPoolSurplus>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_pool_surplus(amount)?;
+        Ok(())
+    }
+
+    pub fn init_lp_mint(ctx: Context<InitLpMint>) -> Result<()> {
+        ctx.accounts.init_lp_mint(&ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn lp_deposit(ctx: Context<LpDeposit>, amount: u64) -> Result<()> {
+        ctx.accounts.lp_deposit(amount)?;
+        Ok(())
+    }
+
+    pub fn lp_withdraw(ctx: Context<LpWithdraw>, shares: u64) -> Result<()> {
+        ctx.accounts.lp_withdraw(shares)?;
+        Ok(())
+    }
+
+    pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStats> {
+        ctx.accounts.get_pool_stats()
+    }
+
+    // === Protocol Instructions ===
+    pub fn init_protocol_config(
+        ctx: Context<InitProtocolConfig>,
+        opening_fee_bps: u16,
+        winnings_fee_bps: u16,
+        referral_share_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.init_protocol_config(
+            opening_fee_bps,
+            winnings_fee_bps,
+            referral_share_bps,
+            &ctx.bumps
+        )?;
+        Ok(())
+    }
+
+    pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+        ctx.accounts.queue_config_change(change, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        ctx.accounts.execute_config_change()?;
+        Ok(())
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        ctx.accounts.cancel_config_change()?;
+        Ok(())
+    }
+
+    pub fn propose_protocol_authority(ctx: Context<ProposeProtocolAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.propose_protocol_authority(new_authority)?;
+        Ok(())
+    }
+
+    pub fn accept_protocol_authority(ctx: Context<AcceptProtocolAuthority>) -> Result<()> {
+        ctx.accounts.accept_protocol_authority()?;
+        Ok(())
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_fees(amount)?;
+        Ok(())
+    }
+
+    pub fn grant_role(ctx: Context<UpdateRole>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.grant_role(role, member)?;
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<UpdateRole>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.revoke_role(role, member)?;
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.set_paused(paused)?;
+        Ok(())
+    }
+
+    // === Referral Instructions ===
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        ctx.accounts.register_referrer(&ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        ctx.accounts.claim_referral_rewards()?;
+        Ok(())
+    }
+
+    // === Risk Instructions ===
+    pub fn init_exposure_book(
+        ctx: Context<InitExposureBook>,
+        base_price: u64,
+        bucket_width: u64,
+        max_net_exposure: u64,
+    ) -> Result<()> {
+        ctx.accounts.init_exposure_book(base_price, bucket_width, max_net_exposure, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn update_exposure_limit(ctx: Context<UpdateExposureLimit>, max_net_exposure: u64) -> Result<()> {
+        ctx.accounts.update_exposure_limit(max_net_exposure)?;
+        Ok(())
+    }
+
+    pub fn update_user_limits(
+        ctx: Context<UpdateUserLimits>,
+        max_open_positions: u32,
+        max_notional: u64,
+        max_payout: u64,
+    ) -> Result<()> {
+        ctx.accounts.update_user_limits(max_open_positions, max_notional, max_payout)?;
+        Ok(())
+    }
+
+    // === Order Book Instructions ===
+    pub fn init_order_book(ctx: Context<InitOrderBook>) -> Result<()> {
+        ctx.accounts.init_order_book(&ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        position_type: PositionType,
+        lower_bound: u64,
+        upper_bound: u64,
+        size: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        ctx.accounts.place_order(position_type, lower_bound, upper_bound, size, expiry, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        ctx.accounts.cancel_order()?;
+        Ok(())
+    }
+
+    pub fn match_orders(ctx: Context<MatchOrders>) -> Result<()> {
+        ctx.accounts.match_orders(&ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn fill_order(
+        ctx: Context<FillOrder>,
+        order_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.fill_order(order_id, amount, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn create_paired_position(
+        ctx: Context<CreatePairedPosition>,
+        lower_bound: u64,
+        upper_bound: u64,
+        stake: u64,
+    ) -> Result<()> {
+        ctx.accounts.create_paired_position(lower_bound, upper_bound, stake, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn create_position_with_quote(ctx: Context<CreatePositionWithQuote>, quote: Quote) -> Result<()> {
+        ctx.accounts.create_position_with_quote(quote, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn settle_paired_position(ctx: Context<SettlePairedPosition>) -> Result<()> {
+        ctx.accounts.settle_paired_position()?;
+        Ok(())
+    }
+
+    pub fn claim_paired_position(ctx: Context<ClaimPairedPosition>) -> Result<()> {
+        ctx.accounts.claim_paired_position()?;
+        Ok(())
+    }
+
+    // === Parimutuel Instructions ===
+    pub fn init_parimutuel_market(
+        ctx: Context<InitParimutuelMarket>,
+        epoch_length: i64,
+        stake_window: i64,
+        band_width_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.init_parimutuel_market(epoch_length, stake_window, band_width_bps, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn open_epoch(ctx: Context<OpenEpoch>, epoch_index: u64) -> Result<()> {
+        ctx.accounts.open_epoch(epoch_index, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn stake_epoch(ctx: Context<StakeEpoch>, position_type: PositionType, amount: u64) -> Result<()> {
+        ctx.accounts.stake_epoch(position_type, amount, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn settle_epoch(ctx: Context<SettleEpoch>) -> Result<()> {
+        ctx.accounts.settle_epoch()?;
+        Ok(())
+    }
+
+    pub fn claim_epoch(ctx: Context<ClaimEpoch>) -> Result<()> {
+        ctx.accounts.claim_epoch()?;
+        Ok(())
+    }
+
     // === Position Management Instructions ===
     pub fn create_position(
         ctx: Context<CreatePosition>,
         position_type: PositionType,
         lower_bound: u64,
         upper_bound: u64,
-        order_id: u64,
-        amount: u64
-    ) -> Result<()> {
+        amount: u64,
+        tokenize: bool,
+    ) -> Result<u64> {
         ctx.accounts.create_position(
             position_type, 
             lower_bound, 
             upper_bound, 
-            order_id, 
             amount, 
+            tokenize,
             &ctx.bumps
-        )?;
-        Ok(())
+        )
     }
     
     pub fn check_position(ctx: Context<CheckPosition>) -> Result<()> {
@@ -69,8 +313,95 @@ pub mod vault {
         Ok(())
     }
     
+    pub fn create_position_ladder<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreatePositionLadder<'info>>,
+        rungs: Vec<LadderRung>,
+    ) -> Result<u64> {
+        ctx.accounts.create_position_ladder(rungs, ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn check_position_ladder<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CheckPositionLadder<'info>>,
+        user: Pubkey,
+        ladder_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.check_position_ladder(user, ladder_id, ctx.remaining_accounts, &ctx.bumps)?;
+        Ok(())
+    }
+
     pub fn claim_position(ctx: Context<ClaimPosition>) -> Result<()> {
         ctx.accounts.claim(&ctx.bumps)?;
         Ok(())
     }
+
+    pub fn tokenize_position(ctx: Context<TokenizePosition>) -> Result<()> {
+        ctx.accounts.tokenize_position()?;
+        Ok(())
+    }
+
+    pub fn claim_tokenized_position(ctx: Context<ClaimTokenizedPosition>) -> Result<()> {
+        ctx.accounts.claim_tokenized_position(&ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn set_roll_config(ctx: Context<SetRollConfig>, roll_config: Option<RollConfig>) -> Result<()> {
+        ctx.accounts.set_roll_config(roll_config)?;
+        Ok(())
+    }
+
+    pub fn roll_position(ctx: Context<RollPosition>) -> Result<u64> {
+        ctx.accounts.roll_position(&ctx.bumps)
+    }
+
+    // === Compressed Position Instructions ===
+    pub fn init_position_tree(ctx: Context<InitPositionTree>, tree_id: u64) -> Result<()> {
+        ctx.accounts.init_position_tree(tree_id, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn create_compressed_position(
+        ctx: Context<CreateCompressedPosition>,
+        position_type: PositionType,
+        lower_bound: u64,
+        upper_bound: u64,
+        amount: u64,
+    ) -> Result<u64> {
+        ctx.accounts.create_compressed_position(position_type, lower_bound, upper_bound, amount, &ctx.bumps)
+    }
+
+    pub fn settle_compressed_position(
+        ctx: Context<SettleCompressedPosition>,
+        root: [u8; 32],
+        position: CompressedPosition,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.settle_compressed_position(root, position, proof, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn claim_compressed_position(
+        ctx: Context<ClaimCompressedPosition>,
+        root: [u8; 32],
+        position: CompressedPosition,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.claim_compressed_position(root, position, proof, &ctx.bumps)?;
+        Ok(())
+    }
+
+    // === Migration Instructions ===
+    pub fn migrate_vault_state(ctx: Context<MigrateVaultState>) -> Result<()> {
+        ctx.accounts.migrate_vault_state()?;
+        Ok(())
+    }
+
+    pub fn migrate_position(ctx: Context<MigratePosition>, position_id: u64) -> Result<()> {
+        ctx.accounts.migrate_position(position_id, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn migrate_trading_pool(ctx: Context<MigrateTradingPool>) -> Result<()> {
+        ctx.accounts.migrate_trading_pool()?;
+        Ok(())
+    }
 }
\ No newline at end of file
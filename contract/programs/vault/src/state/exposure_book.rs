@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::state::PositionType;
+use crate::error::ErrorCode;
+
+pub const EXPOSURE_BUCKETS: usize = 32;
+
+// Aggregated open notional per price bucket for one market
+#[account]
+#[derive(InitSpace)]
+pub struct ExposureBook {
+    pub version: u8,
+    pub trading_pool: Pubkey,
+    pub base_price: u64,
+    pub bucket_width: u64,
+    pub max_net_exposure: u64,
+    pub stay_in_exposure: [u64; EXPOSURE_BUCKETS],
+    pub breakout_exposure: [u64; EXPOSURE_BUCKETS],
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+//<------------------Helper functions-------------------->
+
+impl ExposureBook {
+    pub const VERSION: u8 = 1;
+
+    // Prices outside the tracked range land in the edge buckets
+    pub fn bucket_index(&self, price: u64) -> usize {
+        let index = price.saturating_sub(self.base_price) / self.bucket_width;
+        (index as usize).min(EXPOSURE_BUCKETS - 1)
+    }
+
+    // Difference between StayIn and Breakout notional in a bucket
+    pub fn net_exposure(&self, index: usize) -> u64 {
+        self.stay_in_exposure[index].abs_diff(self.breakout_exposure[index])
+    }
+
+    // Add a position's notional to every bucket its band covers.
+    // Fails if any bucket ends up above the limit and the position increased
+    // its net exposure, so hedging positions are always accepted
+    pub fn add_exposure(
+        &mut self,
+        position_type: PositionType,
+        lower_bound: u64,
+        upper_bound: u64,
+        amount: u64,
+    ) -> Result<()> {
+        for index in self.bucket_index(lower_bound)..=self.bucket_index(upper_bound) {
+            let net_before = self.net_exposure(index);
+
+            let side = match position_type {
+                PositionType::StayIn => &mut self.stay_in_exposure[index],
+                PositionType::Breakout => &mut self.breakout_exposure[index],
+            };
+            *side = side.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+            let net_after = self.net_exposure(index);
+            require!(
+                net_after <= self.max_net_exposure || net_after <= net_before,
+                ErrorCode::ExposureLimitExceeded
+            );
+        }
+
+        Ok(())
+    }
+
+    // Remove a settled position's notional.
+    // Saturates so positions opened before the book existed can still settle
+    pub fn remove_exposure(
+        &mut self,
+        position_type: PositionType,
+        lower_bound: u64,
+        upper_bound: u64,
+        amount: u64,
+    ) {
+        for index in self.bucket_index(lower_bound)..=self.bucket_index(upper_bound) {
+            let side = match position_type {
+                PositionType::StayIn => &mut self.stay_in_exposure[index],
+                PositionType::Breakout => &mut self.breakout_exposure[index],
+            };
+            *side = side.saturating_sub(amount);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::constants::{DEFAULT_MAX_UTILIZATION_BPS, DEFAULT_POSITION_DURATION};
+use crate::error::ErrorCode;
+use crate::state::{PositionState, PositionStatus, PositionType, SettlementData, TradingPool, VaultState};
+
+// Layouts of the accounts the program wrote before versioning, as deployed.
+// migrate_* instructions read them and rewrite the account at the current version
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct VaultStateV0 {
+    pub authority: Pubkey,
+    pub vault_bump: u8,
+    pub state_bump: u8,
+}
+
+// Positions were keyed by a client chosen order id and all ran for the default duration
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct PositionStateV0 {
+    pub user: Pubkey,
+    pub position_type: PositionType,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub start_time: i64,
+    pub order_id: u64,
+    pub status: PositionStatus,
+    pub amount: u64,
+    pub settlement_data: Option<SettlementData>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct TradingPoolV0 {
+    pub authority: Pubkey,
+    pub total_active_amount: u64,
+    pub total_pool_amount: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+//<------------------Helper functions-------------------->
+
+// Open position counters start at zero and next_position_id at the first id. Both
+// are brought up to date as the user's positions go through migrate_position
+impl From<VaultStateV0> for VaultState {
+    fn from(legacy: VaultStateV0) -> Self {
+        VaultState {
+            version: VaultState::VERSION,
+            authority: legacy.authority,
+            next_position_id: 0,
+            open_positions: 0,
+            open_notional: 0,
+            open_max_payout: 0,
+            vault_bump: legacy.vault_bump,
+            state_bump: legacy.state_bump,
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl From<PositionStateV0> for PositionState {
+    fn from(legacy: PositionStateV0) -> Self {
+        let settlement_data = legacy.settlement_data.unwrap_or(SettlementData {
+            settlement_time: 0,
+            settlement_price: 0,
+            payout_percentage: 0,
+        });
+
+        let mut position = PositionState {
+            version: PositionState::VERSION,
+            position_type: legacy.position_type as u8,
+            status: legacy.status as u8,
+            bump: legacy.bump,
+            is_settled: legacy.settlement_data.is_some() as u8,
+            payout_percentage: settlement_data.payout_percentage,
+            has_roll_config: 0,
+            rolls_remaining: 0,
+            roll_band_width_bps: 0,
+            roll_reinvest: 0,
+            user: legacy.user,
+            position_mint: Pubkey::default(),
+            ladder_rung: 0,
+            reserved: [0; 60],
+            lower_bound: legacy.lower_bound,
+            upper_bound: legacy.upper_bound,
+            start_time: legacy.start_time,
+            duration: DEFAULT_POSITION_DURATION,
+            // The order id is already the position's address seed
+            position_id: legacy.order_id,
+            amount: legacy.amount,
+            settlement_time: settlement_data.settlement_time,
+            settlement_price: settlement_data.settlement_price,
+        };
+        position.set_roll_config(None);
+
+        position
+    }
+}
+
+// Liabilities start at zero, migrate_position adds those of each open position
+impl From<TradingPoolV0> for TradingPool {
+    fn from(legacy: TradingPoolV0) -> Self {
+        TradingPool {
+            version: TradingPool::VERSION,
+            authority: legacy.authority,
+            pending_authority: None,
+            total_active_amount: legacy.total_active_amount,
+            total_pool_amount: legacy.total_pool_amount,
+            worst_case_liability: 0,
+            expected_liability: 0,
+            max_utilization_bps: DEFAULT_MAX_UTILIZATION_BPS,
+            max_user_open_positions: 0,
+            max_user_notional: 0,
+            max_user_payout: 0,
+            min_position_size: VaultState::MIN_ORDER_AMOUNT,
+            max_position_size: 0,
+            max_position_liquidity_bps: 0,
+            bump: legacy.bump,
+            vault_bump: legacy.vault_bump,
+            lp_mint_bump: 0,
+            reserved: [0; 64],
+        }
+    }
+}
+
+// Legacy accounts are told apart by their size, since the oldest have no version byte
+pub fn read_legacy_account<T, L>(account: &AccountInfo, legacy_space: usize, space: usize) -> Result<L>
+where
+    T: Discriminator,
+    L: AnchorDeserialize,
+{
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && &data[..8] == T::DISCRIMINATOR,
+        ErrorCode::InvalidAccountLayout
+    );
+    require!(data.len() != space, ErrorCode::AccountAlreadyMigrated);
+    require!(data.len() == legacy_space, ErrorCode::InvalidAccountLayout);
+
+    L::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::InvalidAccountLayout))
+}
+
+// Resize an account to its new layout, with the payer topping up rent
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+        );
+
+        transfer(cpi_ctx, top_up)?;
+    }
+
+    account.realloc(space, false)?;
+
+    Ok(())
+}
+
+pub fn write_migrated_account<'info, T>(
+    account: &AccountInfo<'info>,
+    migrated: &T,
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()>
+where
+    T: AccountSerialize,
+{
+    grow_account(account, space, payer, system_program)?;
+    migrated.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])
+}
@@ -8,4 +8,43 @@ pub mod settlement;
 pub use settlement::*;
 
 pub mod trading_pool;
-pub use trading_pool::*;
\ No newline at end of file
+pub use trading_pool::*;
+
+pub mod exposure_book;
+pub use exposure_book::*;
+
+pub mod referrer;
+pub use referrer::*;
+
+pub mod queued_change;
+pub use queued_change::*;
+
+pub mod roles;
+pub use roles::*;
+
+pub mod protocol_config;
+pub use protocol_config::*;
+
+pub mod order_book;
+pub use order_book::*;
+
+pub mod paired_position;
+pub use paired_position::*;
+
+pub mod quote;
+pub use quote::*;
+
+pub mod parimutuel;
+pub use parimutuel::*;
+
+pub mod user_stats;
+pub use user_stats::*;
+
+pub mod position_index;
+pub use position_index::*;
+
+pub mod position_tree;
+pub use position_tree::*;
+
+pub mod legacy;
+pub use legacy::*;
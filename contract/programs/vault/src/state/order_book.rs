@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+use crate::state::PositionType;
+use crate::error::ErrorCode;
+
+// On-chain order book for one market
+#[account]
+#[derive(InitSpace)]
+pub struct OrderBook {
+    pub version: u8,
+    pub trading_pool: Pubkey,
+    pub next_order_id: u64,
+    pub next_match_id: u64,
+    pub open_orders: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub reserved: [u8; 64],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    pub version: u8,
+    pub order_book: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub position_type: PositionType,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub size: u64,
+    pub filled_amount: u64,
+    pub created_at: i64,
+    pub expiry: i64,
+    pub status: OrderStatus,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum OrderStatus {
+    Open,
+    Filled,
+}
+
+//<------------------Helper functions-------------------->
+
+impl OrderBook {
+    pub const VERSION: u8 = 1;
+
+    pub fn take_order_id(&mut self) -> Result<u64> {
+        let order_id = self.next_order_id;
+        self.next_order_id = order_id.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.open_orders = self.open_orders.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(order_id)
+    }
+
+    pub fn take_match_id(&mut self) -> Result<u64> {
+        let match_id = self.next_match_id;
+        self.next_match_id = match_id.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(match_id)
+    }
+
+    pub fn close_order(&mut self) -> Result<()> {
+        self.open_orders = self.open_orders.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
+
+impl Order {
+    pub const VERSION: u8 = 1;
+
+    pub fn remaining_amount(&self) -> u64 {
+        self.size.saturating_sub(self.filled_amount)
+    }
+
+    pub fn is_expired(&self, current_time: i64) -> bool {
+        current_time >= self.expiry
+    }
+
+    // Record a fill, marking the order filled once nothing remains
+    pub fn fill(&mut self, amount: u64) -> Result<()> {
+        require!(self.status == OrderStatus::Open, ErrorCode::OrderNotOpen);
+        require!(amount <= self.remaining_amount(), ErrorCode::OrderOverfilled);
+
+        self.filled_amount = self.filled_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        if self.remaining_amount() == 0 {
+            self.status = OrderStatus::Filled;
+        }
+
+        Ok(())
+    }
+}
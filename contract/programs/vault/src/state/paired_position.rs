@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::state::{PositionStatus, PositionType, SettlementData};
+use crate::error::ErrorCode;
+
+// StayIn and Breakout stakes on the same band, escrowed against each other
+// instead of against the trading pool
+#[account]
+#[derive(InitSpace)]
+pub struct PairedPosition {
+    pub version: u8,
+    pub match_id: u64,
+    pub order_book: Pubkey,
+    pub stay_in_user: Pubkey,
+    pub breakout_user: Pubkey,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub stay_in_stake: u64,
+    pub breakout_stake: u64,
+    pub start_time: i64,
+    pub duration: i64,
+    pub status: PositionStatus,
+    pub settlement_data: Option<SettlementData>,
+    pub stay_in_payout: u64,
+    pub breakout_payout: u64,
+    pub stay_in_claimed: bool,
+    pub breakout_claimed: bool,
+    pub bump: u8,
+    pub escrow_bump: u8,
+    // Paid the escrow's rent-exempt top-up, refunded on the last claim
+    pub rent_payer: Pubkey,
+    pub reserved: [u8; 32],
+}
+
+//<------------------Helper functions-------------------->
+
+impl PairedPosition {
+    pub const VERSION: u8 = 1;
+
+    pub fn get_expiry_time(&self) -> i64 {
+        self.start_time + self.duration
+    }
+
+    pub fn is_outside_range(&self, current_price: u64) -> bool {
+        current_price < self.lower_bound || current_price > self.upper_bound
+    }
+
+    // Both stakes
+    pub fn escrow_amount(&self) -> Result<u64> {
+        self.stay_in_stake
+            .checked_add(self.breakout_stake)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    pub fn stake_of(&self, side: PositionType) -> u64 {
+        match side {
+            PositionType::StayIn => self.stay_in_stake,
+            PositionType::Breakout => self.breakout_stake,
+        }
+    }
+
+    pub fn side_of(&self, user: &Pubkey) -> Option<PositionType> {
+        if *user == self.stay_in_user {
+            Some(PositionType::StayIn)
+        } else if *user == self.breakout_user {
+            Some(PositionType::Breakout)
+        } else {
+            None
+        }
+    }
+
+    // Settle once the price leaves the band or the position expires.
+    // The loser keeps the usual time-weighted refund and the winner receives
+    // the rest of the escrow, so payouts always sum to both stakes
+    pub fn settle(&mut self, settlement_time: i64, settlement_price: u64) -> Result<bool> {
+        require!(self.status == PositionStatus::Active, ErrorCode::PositionAlreadySettled);
+
+        let is_outside_range = self.is_outside_range(settlement_price);
+        let is_expired = settlement_time >= self.get_expiry_time();
+
+        if !is_outside_range && !is_expired {
+            return Ok(false);
+        }
+
+        let escrow_amount = self.escrow_amount()?;
+
+        if is_outside_range {
+            // Breakout wins, StayIn is refunded for the time it held
+            let elapsed_seconds = (settlement_time - self.start_time).min(self.duration).max(0);
+            let stay_in_refund =
+                (self.stay_in_stake as u128 * elapsed_seconds as u128 / self.duration as u128) as u64;
+
+            self.stay_in_payout = stay_in_refund;
+            self.breakout_payout = escrow_amount - stay_in_refund;
+        } else {
+            // Price stayed in range until expiry, StayIn takes the escrow
+            self.stay_in_payout = escrow_amount;
+            self.breakout_payout = 0;
+        }
+
+        // Payouts are kept exactly above, the percentage is StayIn's and saturates
+        // when the stakes are uneven
+        let payout_percentage = (self.stay_in_payout as u128 * 100 / self.stay_in_stake as u128)
+            .min(u8::MAX as u128) as u8;
+
+        self.status = PositionStatus::Settled;
+        self.settlement_data = Some(SettlementData {
+            settlement_time,
+            settlement_price,
+            payout_percentage,
+        });
+
+        Ok(true)
+    }
+
+    // Top the escrow up to its rent-exempt minimum so claims can drain it in
+    // any order, and remember who paid
+    pub fn fund_escrow_rent<'info>(
+        &mut self,
+        escrow: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        self.rent_payer = payer.key();
+
+        let shortfall = Rent::get()?.minimum_balance(0).saturating_sub(escrow.lamports());
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: escrow.clone(),
+                },
+            );
+
+            transfer(cpi_ctx, shortfall)?;
+        }
+
+        Ok(())
+    }
+
+    // After the last claim only the rent top-up is left, it goes back to the payer
+    pub fn refund_escrow_rent<'info>(
+        &self,
+        paired_position: Pubkey,
+        escrow: &AccountInfo<'info>,
+        rent_payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        require!(self.status == PositionStatus::Claimed, ErrorCode::PositionNotSettled);
+        require_keys_eq!(rent_payer.key(), self.rent_payer, ErrorCode::UnauthorizedAccess);
+
+        let remaining = escrow.lamports();
+        if remaining == 0 {
+            return Ok(());
+        }
+
+        let escrow_seeds = &[
+            b"paired_escrow".as_ref(),
+            paired_position.as_ref(),
+            &[self.escrow_bump],
+        ];
+        let signer_seeds = &[&escrow_seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            Transfer {
+                from: escrow.clone(),
+                to: rent_payer.clone(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, remaining)
+    }
+
+    // Mark one side as claimed and return its payout
+    pub fn claim(&mut self, side: PositionType) -> Result<u64> {
+        require!(self.status == PositionStatus::Settled, ErrorCode::PositionNotSettled);
+
+        let (claimed, payout) = match side {
+            PositionType::StayIn => (&mut self.stay_in_claimed, self.stay_in_payout),
+            PositionType::Breakout => (&mut self.breakout_claimed, self.breakout_payout),
+        };
+        require!(!*claimed, ErrorCode::AlreadyClaimed);
+        *claimed = true;
+
+        if self.stay_in_claimed && self.breakout_claimed {
+            self.status = PositionStatus::Claimed;
+        }
+
+        Ok(payout)
+    }
+}
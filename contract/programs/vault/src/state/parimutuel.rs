@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::state::PositionType;
+use crate::error::ErrorCode;
+
+// Parimutuel product for a market: fixed-length epochs aligned to the unix epoch,
+// so daily epochs start at 00:00 UTC
+#[account]
+#[derive(InitSpace)]
+pub struct ParimutuelMarket {
+    pub version: u8,
+    pub trading_pool: Pubkey,
+    pub epoch_length: i64,
+    pub stake_window: i64,
+    pub band_width_bps: u16,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum EpochStatus {
+    Open,
+    Settled,
+}
+
+// Side totals for one epoch. The band is fixed around the price when the epoch opens
+#[account]
+#[derive(InitSpace)]
+pub struct Epoch {
+    pub version: u8,
+    pub market: Pubkey,
+    pub epoch_index: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub open_price: u64,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub stay_in_total: u64,
+    pub breakout_total: u64,
+    pub status: EpochStatus,
+    // None once settled means one side was empty and every stake is refunded
+    pub winning_side: Option<PositionType>,
+    pub settlement_time: i64,
+    pub settlement_price: u64,
+    pub fee: u64,
+    pub distributable: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub reserved: [u8; 64],
+}
+
+// A user's stakes on both sides of one epoch
+#[account]
+#[derive(InitSpace)]
+pub struct EpochStake {
+    pub version: u8,
+    pub epoch: Pubkey,
+    pub user: Pubkey,
+    pub stay_in_amount: u64,
+    pub breakout_amount: u64,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+//<------------------Helper functions-------------------->
+
+impl ParimutuelMarket {
+    pub const VERSION: u8 = 1;
+
+    pub fn validate(epoch_length: i64, stake_window: i64, band_width_bps: u16) -> Result<()> {
+        require!(
+            epoch_length > 0
+                && stake_window > 0
+                && stake_window < epoch_length
+                && band_width_bps > 0
+                && (band_width_bps as u64) < BPS_DENOMINATOR,
+            ErrorCode::InvalidEpochConfig
+        );
+
+        Ok(())
+    }
+
+    pub fn epoch_index_at(&self, timestamp: i64) -> u64 {
+        (timestamp.max(0) / self.epoch_length) as u64
+    }
+
+    // Band of +/- band_width_bps around the opening price
+    pub fn band_around(&self, price: u64) -> (u64, u64) {
+        let half_width = (price as u128 * self.band_width_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+
+        (price.saturating_sub(half_width), price.saturating_add(half_width))
+    }
+}
+
+impl Epoch {
+    pub const VERSION: u8 = 1;
+
+    pub fn is_outside_range(&self, current_price: u64) -> bool {
+        current_price < self.lower_bound || current_price > self.upper_bound
+    }
+
+    pub fn total(&self) -> Result<u64> {
+        self.stay_in_total
+            .checked_add(self.breakout_total)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    pub fn side_total(&self, side: PositionType) -> u64 {
+        match side {
+            PositionType::StayIn => self.stay_in_total,
+            PositionType::Breakout => self.breakout_total,
+        }
+    }
+
+    pub fn add_stake(&mut self, side: PositionType, amount: u64) -> Result<()> {
+        let total = match side {
+            PositionType::StayIn => &mut self.stay_in_total,
+            PositionType::Breakout => &mut self.breakout_total,
+        };
+        *total = total.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Fix the winning side and the fee taken from the losing side's stake
+    pub fn settle(
+        &mut self,
+        winning_side: PositionType,
+        settlement_time: i64,
+        settlement_price: u64,
+        winnings_fee_bps: u16,
+    ) -> Result<()> {
+        require!(self.status == EpochStatus::Open, ErrorCode::PositionAlreadySettled);
+
+        let losing_total = self.total()? - self.side_total(winning_side);
+
+        self.status = EpochStatus::Settled;
+        self.settlement_time = settlement_time;
+        self.settlement_price = settlement_price;
+
+        if self.side_total(winning_side) == 0 || losing_total == 0 {
+            // Nobody to pay or nothing to win, every stake goes back
+            self.winning_side = None;
+            return Ok(());
+        }
+
+        self.fee = (losing_total as u128 * winnings_fee_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        self.distributable = losing_total - self.fee;
+        self.winning_side = Some(winning_side);
+
+        Ok(())
+    }
+
+    // Winners get their stake back plus a pro rata share of the losing side
+    pub fn payout_for(&self, stake: &EpochStake) -> Result<u64> {
+        require!(self.status == EpochStatus::Settled, ErrorCode::EpochNotSettled);
+
+        let Some(winning_side) = self.winning_side else {
+            return stake.stay_in_amount
+                .checked_add(stake.breakout_amount)
+                .ok_or(error!(ErrorCode::MathOverflow));
+        };
+
+        let winning_stake = stake.amount(winning_side);
+        let share = (winning_stake as u128 * self.distributable as u128
+            / self.side_total(winning_side) as u128) as u64;
+
+        winning_stake.checked_add(share).ok_or(error!(ErrorCode::MathOverflow))
+    }
+}
+
+impl EpochStake {
+    pub const VERSION: u8 = 1;
+
+    pub fn amount(&self, side: PositionType) -> u64 {
+        match side {
+            PositionType::StayIn => self.stay_in_amount,
+            PositionType::Breakout => self.breakout_amount,
+        }
+    }
+
+    pub fn add(&mut self, side: PositionType, amount: u64) -> Result<()> {
+        let staked = match side {
+            PositionType::StayIn => &mut self.stay_in_amount,
+            PositionType::Breakout => &mut self.breakout_amount,
+        };
+        *staked = staked.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+// Position ids covered by one index page
+pub const POSITION_INDEX_PAGE_SIZE: u64 = 32;
+
+// One page of a user's open position ids. Page n covers ids n * 32 to n * 32 + 31,
+// so a client reads pages 0 to next_position_id / 32 to list every open position.
+// Pages that do not exist list nothing. Compressed positions have no position id
+// and are not indexed
+#[account]
+#[derive(InitSpace)]
+pub struct PositionIndex {
+    pub version: u8,
+    pub user: Pubkey,
+    pub page: u64,
+    #[max_len(POSITION_INDEX_PAGE_SIZE)]
+    pub position_ids: Vec<u64>,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+//<------------------Helper functions-------------------->
+
+impl PositionIndex {
+    pub const VERSION: u8 = 1;
+
+    pub fn page_of(position_id: u64) -> u64 {
+        position_id / POSITION_INDEX_PAGE_SIZE
+    }
+
+    // Pages are created on first use with init_if_needed
+    pub fn ensure_initialized(&mut self, user: Pubkey, page: u64, bump: u8) {
+        if self.user == Pubkey::default() {
+            self.version = Self::VERSION;
+            self.user = user;
+            self.page = page;
+            self.bump = bump;
+        }
+    }
+
+    pub fn insert(&mut self, position_id: u64) -> Result<()> {
+        require!(Self::page_of(position_id) == self.page, ErrorCode::InvalidPositionIndex);
+
+        if !self.position_ids.contains(&position_id) {
+            self.position_ids.push(position_id);
+        }
+
+        Ok(())
+    }
+
+    // Removing an id that is not listed is a no-op
+    pub fn remove(&mut self, position_id: u64) -> Result<()> {
+        require!(Self::page_of(position_id) == self.page, ErrorCode::InvalidPositionIndex);
+
+        self.position_ids.retain(|&id| id != position_id);
+
+        Ok(())
+    }
+}
+
+// Claims remove the id from its page. A page left empty is closed with its rent
+// returned, and the next position opened on it creates it again
+pub fn remove_from_index<'info>(
+    position_index: &mut Account<'info, PositionIndex>,
+    position_id: u64,
+    rent_receiver: &AccountInfo<'info>,
+) -> Result<()> {
+    position_index.remove(position_id)?;
+
+    if position_index.position_ids.is_empty() {
+        position_index.close(rent_receiver.clone())?;
+    }
+
+    Ok(())
+}
@@ -1,21 +1,39 @@
 use anchor_lang::prelude::*;
 
 use crate::state::SettlementData;
+use crate::constants::BPS_DENOMINATOR;
 use crate::error::ErrorCode;
 
-#[account]
+// Fixed layout read in place by AccountLoader, so bulk settlement does not pay
+// for Borsh decoding. Enums are stored as their u8 discriminant and optional
+// fields as a value plus a flag, read through the accessors below
+#[account(zero_copy)]
 #[derive(InitSpace)]
 pub struct PositionState {
-    pub user: Pubkey,              
-    pub position_type: PositionType, 
-    pub lower_bound: u64,           
-    pub upper_bound: u64,           
-    pub start_time: i64,            
-    pub order_id: u64,              
-    pub status: PositionStatus,     
-    pub amount: u64,                
-    pub settlement_data: Option<SettlementData>, 
-    pub bump: u8,                   
+    pub version: u8,
+    pub position_type: u8,
+    pub status: u8,
+    pub bump: u8,
+    pub is_settled: u8,
+    pub payout_percentage: u8,
+    pub has_roll_config: u8,
+    pub rolls_remaining: u8,
+    pub roll_band_width_bps: u16,
+    pub roll_reinvest: u8,
+    pub user: Pubkey,
+    // Default pubkey until the position is represented by a token, whose holder has the claim
+    pub position_mint: Pubkey,
+    // 1-based rung within the ladder the position was opened in, 0 outside a ladder
+    pub ladder_rung: u8,
+    pub reserved: [u8; 60],
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub start_time: i64,
+    pub duration: i64,
+    pub position_id: u64,
+    pub amount: u64,
+    pub settlement_time: i64,
+    pub settlement_price: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq,Eq, InitSpace)]
@@ -31,12 +49,33 @@ pub enum PositionStatus {
     Claimed,   
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ReinvestRule {
+    // Reopen with the same amount as the settled position's stake, out of which the
+    // next opening fee is taken
+    Stake,
+    // Reopen with everything the settled position paid out
+    Payout,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct RollConfig {
+    pub rolls_remaining: u8,
+    // Half width of the next band around the price at roll time
+    pub band_width_bps: u16,
+    pub reinvest: ReinvestRule,
+}
+
 //<------------------Helper functions-------------------->
 
 
 impl PositionState {
-    
-    
+    pub const VERSION: u8 = 2;
+
+    // Highest payout_percentage any outcome can produce
+    pub const MAX_PAYOUT_PERCENTAGE: u8 = 200;
+
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         &mut self,
         user: Pubkey,
@@ -44,27 +83,92 @@ impl PositionState {
         lower_bound: u64,
         upper_bound: u64,
         start_time: i64,
-        order_id: u64,
+        duration: i64,
+        position_id: u64,
         amount: u64,
         bump: u8,
     ) -> Result<()> {
+        self.version = Self::VERSION;
         self.user = user;
-        self.position_type = position_type;
+        self.position_type = position_type as u8;
         self.lower_bound = lower_bound;
         self.upper_bound = upper_bound;
         self.start_time = start_time;
-        self.order_id = order_id;
-        self.status = PositionStatus::Active;
+        self.duration = duration;
+        self.position_id = position_id;
+        self.status = PositionStatus::Active as u8;
         self.amount = amount;
-        self.settlement_data = None;
+        self.is_settled = 0;
+        self.settlement_time = 0;
+        self.settlement_price = 0;
+        self.payout_percentage = 0;
+        self.position_mint = Pubkey::default();
+        self.ladder_rung = 0;
+        self.set_roll_config(None);
         self.bump = bump;
         
         Ok(())
     }
+
+    pub fn position_type(&self) -> PositionType {
+        match self.position_type {
+            0 => PositionType::StayIn,
+            _ => PositionType::Breakout,
+        }
+    }
+
+    pub fn status(&self) -> PositionStatus {
+        match self.status {
+            0 => PositionStatus::Active,
+            1 => PositionStatus::Settled,
+            _ => PositionStatus::Claimed,
+        }
+    }
+
+    pub fn settlement_data(&self) -> Option<SettlementData> {
+        (self.is_settled != 0).then_some(SettlementData {
+            settlement_time: self.settlement_time,
+            settlement_price: self.settlement_price,
+            payout_percentage: self.payout_percentage,
+        })
+    }
+
+    pub fn position_mint(&self) -> Option<Pubkey> {
+        (self.position_mint != Pubkey::default()).then_some(self.position_mint)
+    }
+
+    // A ladder is identified by its first rung's position id
+    pub fn ladder_id(&self) -> Option<u64> {
+        (self.ladder_rung != 0).then(|| self.position_id - (self.ladder_rung as u64 - 1))
+    }
+
+    pub fn roll_config(&self) -> Option<RollConfig> {
+        (self.has_roll_config != 0).then_some(RollConfig {
+            rolls_remaining: self.rolls_remaining,
+            band_width_bps: self.roll_band_width_bps,
+            reinvest: match self.roll_reinvest {
+                0 => ReinvestRule::Stake,
+                _ => ReinvestRule::Payout,
+            },
+        })
+    }
+
+    pub fn set_roll_config(&mut self, roll_config: Option<RollConfig>) {
+        let config = roll_config.unwrap_or(RollConfig {
+            rolls_remaining: 0,
+            band_width_bps: 0,
+            reinvest: ReinvestRule::Stake,
+        });
+
+        self.has_roll_config = roll_config.is_some() as u8;
+        self.rolls_remaining = config.rolls_remaining;
+        self.roll_band_width_bps = config.band_width_bps;
+        self.roll_reinvest = config.reinvest as u8;
+    }
     
-    // position expiry time (start_time + 24 hours)
+    // position expiry time (start_time + duration)
     pub fn get_expiry_time(&self) -> i64 {
-        self.start_time + 24 * 60 * 60 
+        self.start_time + self.duration
     }
     
     // if a position is expired
@@ -84,23 +188,32 @@ impl PositionState {
         settlement_price: u64,
         payout_percentage: u8
     ) -> Result<()> {
-        require!(self.status == PositionStatus::Active, ErrorCode::PositionAlreadySettled);
+        require!(self.status() == PositionStatus::Active, ErrorCode::PositionAlreadySettled);
         
-        self.status = PositionStatus::Settled;
-        self.settlement_data = Some(SettlementData {
-            settlement_time,
-            settlement_price,
-            payout_percentage,
-        });
+        self.status = PositionStatus::Settled as u8;
+        self.is_settled = 1;
+        self.settlement_time = settlement_time;
+        self.settlement_price = settlement_price;
+        self.payout_percentage = payout_percentage;
         
         Ok(())
     }
     
+    // Payout in lamports for a given payout_percentage
+    pub fn payout_amount(&self, payout_percentage: u8) -> u64 {
+        (self.amount as u128 * payout_percentage as u128 / 100) as u64
+    }
+
+    // Worst-case payout the pool may owe this position
+    pub fn max_payout(&self) -> u64 {
+        self.payout_amount(Self::MAX_PAYOUT_PERCENTAGE)
+    }
+
     // Mark a position as claimed
     pub fn claim(&mut self) -> Result<()> {
-        require!(self.status == PositionStatus::Settled, ErrorCode::PositionNotSettled);
+        require!(self.status() == PositionStatus::Settled, ErrorCode::PositionNotSettled);
         
-        self.status = PositionStatus::Claimed;
+        self.status = PositionStatus::Claimed as u8;
         
         Ok(())
     }
@@ -111,11 +224,11 @@ pub fn calculate_payout(&self, current_time: i64, current_price: u64) -> u8 {
     let expiry_time = self.get_expiry_time();
     let is_expired = current_time >= expiry_time;
     
-    let total_duration_seconds = 86400; 
+    let total_duration_seconds = self.duration; 
     
     let elapsed_seconds = (current_time - self.start_time).min(total_duration_seconds).max(0);
     
-    match (self.position_type, is_outside_range, is_expired) {
+    match (self.position_type(), is_outside_range, is_expired) {
         // StayIn position outcomes
         (PositionType::StayIn, false, true) => {
             // Price stayed in range until expiry = full win
@@ -155,4 +268,40 @@ pub fn calculate_payout(&self, current_time: i64, current_price: u64) -> u8 {
         },
     }
 }
-}
\ No newline at end of file
+}
+
+impl RollConfig {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.rolls_remaining > 0
+                && self.band_width_bps > 0
+                && (self.band_width_bps as u64) < BPS_DENOMINATOR,
+            ErrorCode::InvalidRollConfig
+        );
+
+        Ok(())
+    }
+
+    // Band recentered on the price at roll time
+    pub fn band_around(&self, price: u64) -> (u64, u64) {
+        let half_width = (price as u128 * self.band_width_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+
+        (price.saturating_sub(half_width), price.saturating_add(half_width))
+    }
+
+    // Config carried by the next position, none once the rolls run out
+    pub fn next(&self) -> Option<RollConfig> {
+        (self.rolls_remaining > 1).then_some(RollConfig {
+            rolls_remaining: self.rolls_remaining - 1,
+            ..*self
+        })
+    }
+
+    // Gross amount reinvested into the next position
+    pub fn reinvest_amount(&self, stake: u64, payout: u64) -> u64 {
+        match self.reinvest {
+            ReinvestRule::Stake => stake,
+            ReinvestRule::Payout => payout,
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::state::{PositionState, PositionStatus, PositionType, SettlementData};
+use crate::error::ErrorCode;
+
+// Leaves per tree are 2^POSITION_TREE_DEPTH
+pub const POSITION_TREE_DEPTH: usize = 14;
+// Updates a proof may lag behind and still be applied
+pub const POSITION_TREE_BUFFER_SIZE: usize = 16;
+
+// Concurrent Merkle tree of compressed positions. Only the root history is kept
+// on-chain, the leaves themselves are read back from the events that wrote them
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct PositionTree {
+    pub tree_id: u64,
+    pub next_leaf_index: u64,
+    // Index of the latest entry in change_logs
+    pub active_index: u64,
+    pub trading_pool: Pubkey,
+    // Latest left node at each level, so appends need no proof
+    pub filled_subtrees: [[u8; 32]; POSITION_TREE_DEPTH],
+    pub change_logs: [ChangeLog; POSITION_TREE_BUFFER_SIZE],
+    pub version: u8,
+    pub bump: u8,
+    pub reserved: [u8; 62],
+}
+
+// Root after one leaf update, with the nodes on that leaf's path
+#[zero_copy]
+#[derive(InitSpace)]
+pub struct ChangeLog {
+    pub root: [u8; 32],
+    pub path: [[u8; 32]; POSITION_TREE_DEPTH],
+    pub index: u64,
+}
+
+// Position stored as a tree leaf instead of an account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CompressedPosition {
+    pub leaf_index: u64,
+    pub user: Pubkey,
+    pub position_type: PositionType,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub start_time: i64,
+    pub duration: i64,
+    pub amount: u64,
+    pub status: PositionStatus,
+    pub settlement_data: Option<SettlementData>,
+}
+
+//<------------------Helper functions-------------------->
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[left, right]).to_bytes()
+}
+
+impl PositionTree {
+    pub const VERSION: u8 = 1;
+    pub const CAPACITY: u64 = 1 << POSITION_TREE_DEPTH;
+
+    pub fn initialize(&mut self, tree_id: u64, trading_pool: Pubkey, bump: u8) {
+        self.version = Self::VERSION;
+        self.tree_id = tree_id;
+        self.trading_pool = trading_pool;
+        self.bump = bump;
+        self.next_leaf_index = 0;
+        self.active_index = 0;
+
+        // Every leaf starts empty
+        let mut node = [0; 32];
+        for level in 0..POSITION_TREE_DEPTH {
+            self.filled_subtrees[level] = node;
+            self.change_logs[0].path[level] = node;
+            node = hash_pair(&node, &node);
+        }
+        self.change_logs[0].root = node;
+        self.change_logs[0].index = 0;
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.change_logs[self.active_index as usize].root
+    }
+
+    // Add a leaf at the next free index, returns that index
+    pub fn append(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        let index = self.next_leaf_index;
+        require!(index < Self::CAPACITY, ErrorCode::PositionTreeFull);
+
+        let mut path = [[0; 32]; POSITION_TREE_DEPTH];
+        let mut node = leaf;
+        let mut empty = [0; 32];
+        for (level, path_node) in path.iter_mut().enumerate() {
+            *path_node = node;
+            if (index >> level) & 1 == 0 {
+                self.filled_subtrees[level] = node;
+                node = hash_pair(&node, &empty);
+            } else {
+                node = hash_pair(&self.filled_subtrees[level], &node);
+            }
+            empty = hash_pair(&empty, &empty);
+        }
+
+        self.next_leaf_index = index + 1;
+        self.push_change_log(node, path, index);
+
+        Ok(index)
+    }
+
+    // Replace a leaf given a proof against any root still in the change logs. The
+    // proof is brought up to date with the updates made since that root
+    pub fn replace_leaf(
+        &mut self,
+        root: [u8; 32],
+        proof: &[[u8; 32]],
+        index: u64,
+        previous_leaf: [u8; 32],
+        new_leaf: [u8; 32],
+    ) -> Result<()> {
+        require!(index < self.next_leaf_index, ErrorCode::InvalidMerkleProof);
+        require!(proof.len() == POSITION_TREE_DEPTH, ErrorCode::InvalidMerkleProof);
+
+        let mut proof: [[u8; 32]; POSITION_TREE_DEPTH] = proof.try_into()
+            .map_err(|_| ErrorCode::InvalidMerkleProof)?;
+
+        let lag = (0..POSITION_TREE_BUFFER_SIZE as u64)
+            .find(|&lag| self.change_logs[self.change_log_index(lag)].root == root)
+            .ok_or(ErrorCode::StaleMerkleRoot)?;
+
+        // Oldest update first. Each one changed the proof node where its path joins this leaf's
+        for lag in (0..lag).rev() {
+            let change_log = &self.change_logs[self.change_log_index(lag)];
+            require!(change_log.index != index, ErrorCode::InvalidMerkleProof);
+
+            let level = (63 - (change_log.index ^ index).leading_zeros()) as usize;
+            proof[level] = change_log.path[level];
+        }
+
+        let (current_root, _) = Self::path_to_root(previous_leaf, &proof, index);
+        require!(current_root == self.root(), ErrorCode::InvalidMerkleProof);
+
+        let (new_root, path) = Self::path_to_root(new_leaf, &proof, index);
+
+        // Keep the nodes later appends hash against in step with this update
+        let last_index = self.next_leaf_index - 1;
+        for (level, node) in path.iter().enumerate() {
+            if index >> level == ((last_index >> level) & !1) {
+                self.filled_subtrees[level] = *node;
+            }
+        }
+
+        self.push_change_log(new_root, path, index);
+
+        Ok(())
+    }
+
+    fn path_to_root(
+        leaf: [u8; 32],
+        proof: &[[u8; 32]; POSITION_TREE_DEPTH],
+        index: u64,
+    ) -> ([u8; 32], [[u8; 32]; POSITION_TREE_DEPTH]) {
+        let mut path = [[0; 32]; POSITION_TREE_DEPTH];
+        let mut node = leaf;
+        for (level, sibling) in proof.iter().enumerate() {
+            path[level] = node;
+            node = if (index >> level) & 1 == 0 {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            };
+        }
+
+        (node, path)
+    }
+
+    // Position in the ring buffer of the change log `lag` updates back
+    fn change_log_index(&self, lag: u64) -> usize {
+        let size = POSITION_TREE_BUFFER_SIZE as u64;
+        ((self.active_index + size - lag) % size) as usize
+    }
+
+    fn push_change_log(&mut self, root: [u8; 32], path: [[u8; 32]; POSITION_TREE_DEPTH], index: u64) {
+        self.active_index = (self.active_index + 1) % POSITION_TREE_BUFFER_SIZE as u64;
+        self.change_logs[self.active_index as usize] = ChangeLog { root, path, index };
+    }
+}
+
+impl CompressedPosition {
+    pub fn leaf(&self) -> Result<[u8; 32]> {
+        Ok(hashv(&[&self.try_to_vec()?]).to_bytes())
+    }
+
+    // Same fields as a pool position account, so payouts follow the same rules
+    pub fn to_position_state(&self) -> Result<PositionState> {
+        let mut position: PositionState = bytemuck::Zeroable::zeroed();
+        position.initialize(
+            self.user,
+            self.position_type,
+            self.lower_bound,
+            self.upper_bound,
+            self.start_time,
+            self.duration,
+            self.leaf_index,
+            self.amount,
+            0,
+        )?;
+        position.status = self.status as u8;
+        if let Some(settlement_data) = self.settlement_data {
+            position.is_settled = 1;
+            position.settlement_time = settlement_data.settlement_time;
+            position.settlement_price = settlement_data.settlement_price;
+            position.payout_percentage = settlement_data.payout_percentage;
+        }
+
+        Ok(position)
+    }
+
+    pub fn update_from(&mut self, position: &PositionState) {
+        self.status = position.status();
+        self.settlement_data = position.settlement_data();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(value: u8) -> [u8; 32] {
+        [value; 32]
+    }
+
+    fn new_tree() -> Box<PositionTree> {
+        let mut tree: Box<PositionTree> = Box::new(bytemuck::Zeroable::zeroed());
+        tree.initialize(0, Pubkey::default(), 255);
+        tree
+    }
+
+    // Root and proof for `index` computed from every leaf, empty leaves being zero
+    fn reference(leaves: &[[u8; 32]], index: u64) -> ([u8; 32], Vec<[u8; 32]>) {
+        let mut level_nodes: Vec<[u8; 32]> = leaves.to_vec();
+        level_nodes.resize(PositionTree::CAPACITY as usize, [0; 32]);
+
+        let mut proof = Vec::with_capacity(POSITION_TREE_DEPTH);
+        let mut position = index as usize;
+        for _ in 0..POSITION_TREE_DEPTH {
+            proof.push(level_nodes[position ^ 1]);
+            level_nodes = level_nodes
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], &pair[1]))
+                .collect();
+            position /= 2;
+        }
+
+        (level_nodes[0], proof)
+    }
+
+    fn assert_error(result: Result<()>, expected: ErrorCode) {
+        match result.unwrap_err() {
+            Error::AnchorError(error) => assert_eq!(error.error_code_number, u32::from(expected)),
+            error => panic!("unexpected error {error:?}"),
+        }
+    }
+
+    #[test]
+    fn append_matches_the_full_tree() {
+        let mut tree = new_tree();
+        let leaves: Vec<[u8; 32]> = (1..=5).map(leaf).collect();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert_eq!(tree.append(*leaf).unwrap(), index as u64);
+            assert_eq!(tree.root(), reference(&leaves[..=index], 0).0);
+        }
+    }
+
+    #[test]
+    fn replace_leaf_with_a_current_root() {
+        let mut tree = new_tree();
+        let mut leaves: Vec<[u8; 32]> = (1..=4).map(leaf).collect();
+        for leaf in leaves.iter() {
+            tree.append(*leaf).unwrap();
+        }
+
+        let (root, proof) = reference(&leaves, 2);
+        tree.replace_leaf(root, &proof, 2, leaves[2], leaf(9)).unwrap();
+
+        leaves[2] = leaf(9);
+        assert_eq!(tree.root(), reference(&leaves, 0).0);
+    }
+
+    #[test]
+    fn replace_leaf_fast_forwards_a_stale_proof() {
+        let mut tree = new_tree();
+        let mut leaves: Vec<[u8; 32]> = (1..=4).map(leaf).collect();
+        for leaf in leaves.iter() {
+            tree.append(*leaf).unwrap();
+        }
+        let (stale_root, stale_proof) = reference(&leaves, 1);
+
+        // Updates in the leaf's sibling, in the other half and past the end since the proof was taken
+        let (root, proof) = reference(&leaves, 0);
+        tree.replace_leaf(root, &proof, 0, leaves[0], leaf(10)).unwrap();
+        leaves[0] = leaf(10);
+
+        let (root, proof) = reference(&leaves, 3);
+        tree.replace_leaf(root, &proof, 3, leaves[3], leaf(11)).unwrap();
+        leaves[3] = leaf(11);
+
+        tree.append(leaf(12)).unwrap();
+        leaves.push(leaf(12));
+
+        tree.replace_leaf(stale_root, &stale_proof, 1, leaves[1], leaf(13)).unwrap();
+        leaves[1] = leaf(13);
+        assert_eq!(tree.root(), reference(&leaves, 0).0);
+    }
+
+    #[test]
+    fn append_after_replacing_the_last_leaf() {
+        let mut tree = new_tree();
+        let mut leaves: Vec<[u8; 32]> = (1..=5).map(leaf).collect();
+        for leaf in leaves.iter() {
+            tree.append(*leaf).unwrap();
+        }
+
+        // Leaf 4 is a left node that the next append hashes against
+        let (root, proof) = reference(&leaves, 4);
+        tree.replace_leaf(root, &proof, 4, leaves[4], leaf(20)).unwrap();
+        leaves[4] = leaf(20);
+
+        for value in 21..=23 {
+            tree.append(leaf(value)).unwrap();
+            leaves.push(leaf(value));
+            assert_eq!(tree.root(), reference(&leaves, 0).0);
+        }
+    }
+
+    #[test]
+    fn replace_leaf_rejects_a_root_past_the_buffer() {
+        let mut tree = new_tree();
+        let mut leaves = vec![leaf(1)];
+        tree.append(leaf(1)).unwrap();
+        let (stale_root, stale_proof) = reference(&leaves, 0);
+
+        for value in 2..=(POSITION_TREE_BUFFER_SIZE as u8 + 1) {
+            tree.append(leaf(value)).unwrap();
+            leaves.push(leaf(value));
+        }
+
+        let result = tree.replace_leaf(stale_root, &stale_proof, 0, leaf(1), leaf(30));
+        assert_error(result, ErrorCode::StaleMerkleRoot);
+    }
+
+    #[test]
+    fn replace_leaf_rejects_a_wrong_previous_leaf() {
+        let mut tree = new_tree();
+        let leaves: Vec<[u8; 32]> = (1..=2).map(leaf).collect();
+        for leaf in leaves.iter() {
+            tree.append(*leaf).unwrap();
+        }
+
+        let (root, proof) = reference(&leaves, 1);
+        let result = tree.replace_leaf(root, &proof, 1, leaf(7), leaf(30));
+        assert_error(result, ErrorCode::InvalidMerkleProof);
+    }
+
+    #[test]
+    fn replace_leaf_rejects_a_leaf_changed_since_the_root() {
+        let mut tree = new_tree();
+        let leaves: Vec<[u8; 32]> = (1..=2).map(leaf).collect();
+        for leaf in leaves.iter() {
+            tree.append(*leaf).unwrap();
+        }
+
+        let (root, proof) = reference(&leaves, 1);
+        tree.replace_leaf(root, &proof, 1, leaves[1], leaf(30)).unwrap();
+
+        let result = tree.replace_leaf(root, &proof, 1, leaves[1], leaf(31));
+        assert_error(result, ErrorCode::InvalidMerkleProof);
+    }
+}
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{BPS_DENOMINATOR, MAX_TIMELOCK_DELAY};
+use crate::error::ErrorCode;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub price_feed_id: [u8; 32],
+    pub position_duration: i64,
+    pub timelock_delay: i64,
+    pub next_change_id: u64,
+    pub opening_fee_bps: u16,
+    pub winnings_fee_bps: u16,
+    pub referral_share_bps: u16,
+    pub total_fees_collected: u64,
+    pub total_fees_withdrawn: u64,
+    pub total_referral_owed: u64,
+    pub paused: bool,
+    pub bump: u8,
+    pub treasury_bump: u8,
+    pub reserved: [u8; 64],
+}
+
+//<------------------Helper functions-------------------->
+
+impl ProtocolConfig {
+    pub const VERSION: u8 = 1;
+
+    pub const MAX_FEE_BPS: u16 = 1_000;
+
+    pub fn validate_fees(opening_fee_bps: u16, winnings_fee_bps: u16, referral_share_bps: u16) -> Result<()> {
+        require!(
+            opening_fee_bps <= Self::MAX_FEE_BPS && winnings_fee_bps <= Self::MAX_FEE_BPS,
+            ErrorCode::FeeTooHigh
+        );
+        require!(
+            referral_share_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::FeeTooHigh
+        );
+
+        Ok(())
+    }
+
+    // Fee charged on the amount staked when a position opens
+    pub fn opening_fee(&self, amount: u64) -> u64 {
+        (amount as u128 * self.opening_fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    // Fee charged on the profit of a winning position, never on the returned stake
+    pub fn winnings_fee(&self, amount: u64, payout_amount: u64) -> u64 {
+        let profit = payout_amount.saturating_sub(amount);
+        (profit as u128 * self.winnings_fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    // Part of the opening fee credited to the referrer
+    pub fn referral_rebate(&self, opening_fee: u64) -> u64 {
+        (opening_fee as u128 * self.referral_share_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    // Apply a config change once its timelock has passed
+    pub fn apply_change(&mut self, change: &ConfigChange) -> Result<()> {
+        change.validate()?;
+
+        match *change {
+            ConfigChange::Fees { opening_fee_bps, winnings_fee_bps, referral_share_bps } => {
+                self.opening_fee_bps = opening_fee_bps;
+                self.winnings_fee_bps = winnings_fee_bps;
+                self.referral_share_bps = referral_share_bps;
+            }
+            ConfigChange::PriceFeed { feed_id } => self.price_feed_id = feed_id,
+            ConfigChange::PositionDuration { duration } => self.position_duration = duration,
+            ConfigChange::TimelockDelay { delay } => self.timelock_delay = delay,
+        }
+
+        Ok(())
+    }
+
+    pub fn record_fee(&mut self, fee: u64) -> Result<()> {
+        self.total_fees_collected = self.total_fees_collected
+            .checked_add(fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Rebates stay in the treasury until the referrer claims them
+    pub fn record_referral_rebate(&mut self, rebate: u64) -> Result<()> {
+        self.total_referral_owed = self.total_referral_owed
+            .checked_add(rebate)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
+
+// Sensitive parameter changes that must wait for the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ConfigChange {
+    Fees {
+        opening_fee_bps: u16,
+        winnings_fee_bps: u16,
+        referral_share_bps: u16,
+    },
+    PriceFeed {
+        feed_id: [u8; 32],
+    },
+    PositionDuration {
+        duration: i64,
+    },
+    TimelockDelay {
+        delay: i64,
+    },
+}
+
+impl ConfigChange {
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            ConfigChange::Fees { opening_fee_bps, winnings_fee_bps, referral_share_bps } => {
+                ProtocolConfig::validate_fees(opening_fee_bps, winnings_fee_bps, referral_share_bps)?;
+            }
+            ConfigChange::PriceFeed { feed_id } => {
+                require!(feed_id != [0; 32], ErrorCode::InvalidConfigChange);
+            }
+            ConfigChange::PositionDuration { duration } => {
+                require!(duration > 0, ErrorCode::InvalidConfigChange);
+            }
+            ConfigChange::TimelockDelay { delay } => {
+                require!(
+                    (0..=MAX_TIMELOCK_DELAY).contains(&delay),
+                    ErrorCode::InvalidConfigChange
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
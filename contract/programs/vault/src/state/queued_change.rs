@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::state::ConfigChange;
+
+// Config change waiting for the protocol timelock
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedChange {
+    pub version: u8,
+    pub change_id: u64,
+    pub proposer: Pubkey,
+    pub change: ConfigChange,
+    pub queued_at: i64,
+    pub executable_at: i64,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+impl QueuedChange {
+    pub const VERSION: u8 = 1;
+
+    pub fn is_executable(&self, current_time: i64) -> bool {
+        current_time >= self.executable_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(queued_at: i64, delay: i64) -> QueuedChange {
+        QueuedChange {
+            version: QueuedChange::VERSION,
+            change_id: 0,
+            proposer: Pubkey::default(),
+            change: ConfigChange::PositionDuration { duration: 86_400 },
+            queued_at,
+            executable_at: queued_at + delay,
+            bump: 255,
+            reserved: [0; 64],
+        }
+    }
+
+    #[test]
+    fn executable_from_its_eta_onwards() {
+        let change = queued(1_000, 86_400);
+
+        assert!(!change.is_executable(1_000));
+        assert!(!change.is_executable(87_399));
+        assert!(change.is_executable(87_400));
+        assert!(change.is_executable(87_401));
+    }
+
+    #[test]
+    fn executable_at_once_without_a_delay() {
+        assert!(queued(1_000, 0).is_executable(1_000));
+    }
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::state::PositionType;
+use crate::error::ErrorCode;
+
+// Price for a band offered by a market maker, signed off-chain with the maker's key.
+// The taker stakes `size` on `position_type` and receives `odds_bps` of it if they win
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct Quote {
+    pub maker: Pubkey,
+    pub order_book: Pubkey,
+    pub position_type: PositionType,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+    pub odds_bps: u32,
+    pub size: u64,
+    pub expiry: i64,
+    pub nonce: u64,
+}
+
+// Marks a quote nonce as consumed so a signed quote can only be filled once
+#[account]
+#[derive(InitSpace)]
+pub struct UsedQuote {
+    pub version: u8,
+    pub maker: Pubkey,
+    pub nonce: u64,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+impl UsedQuote {
+    pub const VERSION: u8 = 1;
+}
+
+//<------------------Helper functions-------------------->
+
+impl Quote {
+    // Ed25519 instruction header: signature count, padding, then one offsets entry
+    const SIGNATURE_OFFSETS_START: usize = 2;
+    const SIGNATURE_OFFSETS_SIZE: usize = 14;
+    const PUBKEY_SIZE: usize = 32;
+
+    // Collateral the maker puts up against the taker's stake
+    pub fn maker_stake(&self) -> Result<u64> {
+        require!(self.odds_bps as u64 > BPS_DENOMINATOR, ErrorCode::InvalidQuote);
+
+        let stake = (self.size as u128)
+            .checked_mul((self.odds_bps as u64 - BPS_DENOMINATOR) as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+
+        u64::try_from(stake).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    // The ed25519 program instruction must carry exactly one signature, by the maker,
+    // over the Borsh encoding of this quote, with all data inside that instruction
+    pub fn verify_signature(&self, ix: &Instruction) -> Result<()> {
+        require!(
+            ix.program_id == ed25519_program::ID && ix.accounts.is_empty(),
+            ErrorCode::InvalidQuoteSignature
+        );
+
+        let data = &ix.data;
+        require!(
+            data.len() >= Self::SIGNATURE_OFFSETS_START + Self::SIGNATURE_OFFSETS_SIZE && data[0] == 1,
+            ErrorCode::InvalidQuoteSignature
+        );
+
+        let read_u16 = |index: usize| -> usize {
+            let start = Self::SIGNATURE_OFFSETS_START + index * 2;
+            u16::from_le_bytes([data[start], data[start + 1]]) as usize
+        };
+
+        let signature_instruction_index = read_u16(1);
+        let public_key_offset = read_u16(2);
+        let public_key_instruction_index = read_u16(3);
+        let message_data_offset = read_u16(4);
+        let message_data_size = read_u16(5);
+        let message_instruction_index = read_u16(6);
+
+        let current_instruction = u16::MAX as usize;
+        require!(
+            signature_instruction_index == current_instruction
+                && public_key_instruction_index == current_instruction
+                && message_instruction_index == current_instruction,
+            ErrorCode::InvalidQuoteSignature
+        );
+
+        let public_key = data
+            .get(public_key_offset..public_key_offset + Self::PUBKEY_SIZE)
+            .ok_or(ErrorCode::InvalidQuoteSignature)?;
+        require!(public_key == self.maker.as_ref(), ErrorCode::InvalidQuoteSignature);
+
+        let message = data
+            .get(message_data_offset..message_data_offset + message_data_size)
+            .ok_or(ErrorCode::InvalidQuoteSignature)?;
+        require!(message == self.try_to_vec()?.as_slice(), ErrorCode::InvalidQuoteSignature);
+
+        Ok(())
+    }
+}
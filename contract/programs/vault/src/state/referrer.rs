@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Referrer {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub referred_positions: u64,
+    pub total_volume: u64,
+    pub total_earnings: u64,
+    pub claimable: u64,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+//<------------------Helper functions-------------------->
+
+impl Referrer {
+    pub const VERSION: u8 = 1;
+
+    // Credit a referred position's volume and rebate
+    pub fn record_referral(&mut self, amount: u64, rebate: u64) -> Result<()> {
+        self.referred_positions = self.referred_positions
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_volume = self.total_volume
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_earnings = self.total_earnings
+            .checked_add(rebate)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.claimable = self.claimable
+            .checked_add(rebate)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+pub const MAX_ROLE_MEMBERS: usize = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    Admin,
+    RiskManager,
+    Pauser,
+    Keeper,
+    FeeCollector,
+    MarketMaker,
+}
+
+// Holders of every delegated role.
+// Admin is the protocol config authority and is not stored here
+#[account]
+#[derive(InitSpace)]
+pub struct Roles {
+    pub version: u8,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub risk_managers: Vec<Pubkey>,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub pausers: Vec<Pubkey>,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub keepers: Vec<Pubkey>,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub fee_collectors: Vec<Pubkey>,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub market_makers: Vec<Pubkey>,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+//<------------------Helper functions-------------------->
+
+impl Roles {
+    pub const VERSION: u8 = 1;
+
+    // Admin is never stored, so asking for its members is an error rather than an empty list
+    fn members(&self, role: Role) -> Result<&Vec<Pubkey>> {
+        match role {
+            Role::Admin => err!(ErrorCode::InvalidRole),
+            Role::RiskManager => Ok(&self.risk_managers),
+            Role::Pauser => Ok(&self.pausers),
+            Role::Keeper => Ok(&self.keepers),
+            Role::FeeCollector => Ok(&self.fee_collectors),
+            Role::MarketMaker => Ok(&self.market_makers),
+        }
+    }
+
+    fn members_mut(&mut self, role: Role) -> Result<&mut Vec<Pubkey>> {
+        match role {
+            Role::Admin => err!(ErrorCode::InvalidRole),
+            Role::RiskManager => Ok(&mut self.risk_managers),
+            Role::Pauser => Ok(&mut self.pausers),
+            Role::Keeper => Ok(&mut self.keepers),
+            Role::FeeCollector => Ok(&mut self.fee_collectors),
+            Role::MarketMaker => Ok(&mut self.market_makers),
+        }
+    }
+
+    // Admin checks go against protocol_config.authority instead
+    pub fn has_role(&self, role: Role, member: &Pubkey) -> Result<bool> {
+        Ok(self.members(role)?.contains(member))
+    }
+
+    pub fn grant(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        let members = self.members_mut(role)?;
+        require!(!members.contains(&member), ErrorCode::RoleAlreadyGranted);
+        require!(members.len() < MAX_ROLE_MEMBERS, ErrorCode::TooManyRoleMembers);
+
+        members.push(member);
+        Ok(())
+    }
+
+    pub fn revoke(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        let members = self.members_mut(role)?;
+        let index = members
+            .iter()
+            .position(|key| *key == member)
+            .ok_or(ErrorCode::RoleNotGranted)?;
+
+        members.swap_remove(index);
+        Ok(())
+    }
+}
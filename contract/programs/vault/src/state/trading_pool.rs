@@ -1,12 +1,202 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+
 #[account]
 #[derive(InitSpace)]
 pub struct TradingPool {
-    pub authority: Pubkey,         
-    pub total_active_amount: u64,  
-    pub total_pool_amount: u64,    
-    pub bump: u8,                
-    pub vault_bump: u8,            
+    pub version: u8,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub total_active_amount: u64,
+    pub total_pool_amount: u64,
+    pub worst_case_liability: u64,
+    pub expected_liability: u64,
+    pub max_utilization_bps: u16,
+    // Per-user caps on open pool positions, zero means no limit
+    pub max_user_open_positions: u32,
+    pub max_user_notional: u64,
+    pub max_user_payout: u64,
+    // Per-position size limits, a max of zero means no fixed cap and a
+    // liquidity share of zero means no cap relative to free liquidity
+    pub min_position_size: u64,
+    pub max_position_size: u64,
+    pub max_position_liquidity_bps: u16,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub lp_mint_bump: u8,
+    pub reserved: [u8; 64],
 }
 
+//<------------------Helper functions-------------------->
+
+impl TradingPool {
+    pub const VERSION: u8 = 1;
+
+    // Shares locked away by a deposit into an empty share supply
+    pub const MIN_LOCKED_SHARES: u64 = 1_000_000;
+
+    // Net asset value owned by liquidity providers after expected payouts
+    pub fn nav(&self) -> u64 {
+        self.total_pool_amount.saturating_sub(self.expected_liability)
+    }
+
+    // Pool funds not reserved for the maximum payout of any open position
+    pub fn free_liquidity(&self) -> u64 {
+        self.total_pool_amount.saturating_sub(self.worst_case_liability)
+    }
+
+    // Share of pool funds reserved for worst-case payouts, in basis points
+    pub fn utilization_bps(&self) -> u64 {
+        if self.total_pool_amount == 0 {
+            return 0;
+        }
+
+        (self.worst_case_liability as u128 * BPS_DENOMINATOR as u128 / self.total_pool_amount as u128)
+            .min(u64::MAX as u128) as u64
+    }
+
+    // Pool must be able to pay the new position's max payout on top of
+    // every open position, without going over the utilization cap
+    pub fn check_solvency(&self, amount: u64, max_payout: u64) -> Result<()> {
+        let pool_after = self.total_pool_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let liability_after = self.worst_case_liability
+            .checked_add(max_payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        require!(
+            liability_after <= pool_after,
+            ErrorCode::InsufficientPoolLiquidity
+        );
+        require!(
+            liability_after as u128 * BPS_DENOMINATOR as u128
+                <= pool_after as u128 * self.max_utilization_bps as u128,
+            ErrorCode::UtilizationCapExceeded
+        );
+
+        Ok(())
+    }
+
+    // Largest position the pool accepts right now, the lower of the fixed cap
+    // and the configured share of free liquidity
+    pub fn max_position_amount(&self) -> u64 {
+        let mut max_amount = if self.max_position_size == 0 {
+            u64::MAX
+        } else {
+            self.max_position_size
+        };
+
+        if self.max_position_liquidity_bps > 0 {
+            let liquidity_cap = self.free_liquidity() as u128 * self.max_position_liquidity_bps as u128
+                / BPS_DENOMINATOR as u128;
+            max_amount = max_amount.min(liquidity_cap as u64);
+        }
+
+        max_amount
+    }
+
+    pub fn check_position_size(&self, amount: u64) -> Result<()> {
+        require!(amount >= self.min_position_size, ErrorCode::AmountTooSmall);
+        require!(amount <= self.max_position_amount(), ErrorCode::PositionTooLarge);
+
+        Ok(())
+    }
+
+    // New position: owes at most its max payout, expected to return its stake
+    pub fn add_liability(&mut self, amount: u64, max_payout: u64) -> Result<()> {
+        self.worst_case_liability = self.worst_case_liability
+            .checked_add(max_payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.expected_liability = self.expected_liability
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Settled position: both liabilities collapse to the known payout
+    pub fn settle_liability(&mut self, amount: u64, max_payout: u64, payout: u64) -> Result<()> {
+        self.worst_case_liability = self.worst_case_liability
+            .checked_sub(max_payout)
+            .and_then(|liability| liability.checked_add(payout))
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.expected_liability = self.expected_liability
+            .checked_sub(amount)
+            .and_then(|liability| liability.checked_add(payout))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Claimed position: payout has left the pool
+    pub fn release_liability(&mut self, payout: u64) -> Result<()> {
+        self.worst_case_liability = self.worst_case_liability
+            .checked_sub(payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.expected_liability = self.expected_liability
+            .checked_sub(payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // Claimed position: stake leaves the active total and the payout leaves the pool
+    pub fn record_claim(&mut self, amount: u64, payout: u64) -> Result<()> {
+        self.total_active_amount = self.total_active_amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_pool_amount = self.total_pool_amount
+            .checked_sub(payout)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.release_liability(payout)
+    }
+
+    // LP shares minted for a deposit, priced at the current NAV. A deposit into
+    // an empty supply is priced 1:1 less the locked minimum
+    pub fn shares_for_deposit(&self, amount: u64, share_supply: u64) -> Result<u64> {
+        if share_supply == 0 {
+            return amount
+                .checked_sub(Self::MIN_LOCKED_SHARES)
+                .ok_or(error!(ErrorCode::AmountTooSmall));
+        }
+
+        let nav = self.nav();
+        require!(nav > 0, ErrorCode::InsufficientPoolBalance);
+
+        let shares = (amount as u128)
+            .checked_mul(share_supply as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / nav as u128;
+
+        u64::try_from(shares).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    // Shares minted to the pool's own token account when a deposit restarts an
+    // empty supply. NAV left behind by earlier LPs is locked with the minimum
+    // instead of going to the depositor, and the supply never returns to zero
+    pub fn locked_shares_for_deposit(&self, share_supply: u64) -> Result<u64> {
+        if share_supply > 0 {
+            return Ok(0);
+        }
+
+        self.nav()
+            .checked_add(Self::MIN_LOCKED_SHARES)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    // Lamports owed for burning LP shares, priced at the current NAV
+    pub fn amount_for_shares(&self, shares: u64, share_supply: u64) -> Result<u64> {
+        require!(shares <= share_supply, ErrorCode::InsufficientFunds);
+
+        let amount = (shares as u128)
+            .checked_mul(self.nav() as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / share_supply as u128;
+
+        u64::try_from(amount).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::state::PositionType;
+use crate::error::ErrorCode;
+
+// Running totals of a user's pool positions. Outcomes are recorded at settlement,
+// payouts at claim
+#[account]
+#[derive(InitSpace)]
+pub struct UserStats {
+    pub version: u8,
+    pub user: Pubkey,
+    pub positions_opened: u64,
+    pub open_positions: u64,
+    pub open_stake: u64,
+    pub total_staked: u64,
+    pub total_paid_out: u64,
+    pub total_fees_paid: u64,
+    pub stay_in_wins: u64,
+    pub stay_in_losses: u64,
+    pub breakout_wins: u64,
+    pub breakout_losses: u64,
+    pub realized_pnl: i64,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+//<------------------Helper functions-------------------->
+
+impl UserStats {
+    pub const VERSION: u8 = 1;
+
+    // Accounts are created on first use with init_if_needed
+    pub fn ensure_initialized(&mut self, user: Pubkey, bump: u8) {
+        if self.user == Pubkey::default() {
+            self.version = Self::VERSION;
+            self.user = user;
+            self.bump = bump;
+        }
+    }
+
+    pub fn record_open(&mut self, stake: u64, opening_fee: u64) -> Result<()> {
+        self.positions_opened = self.positions_opened.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.open_positions = self.open_positions.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.open_stake = self.open_stake.checked_add(stake).ok_or(ErrorCode::MathOverflow)?;
+        self.total_staked = self.total_staked.checked_add(stake).ok_or(ErrorCode::MathOverflow)?;
+        self.total_fees_paid = self.total_fees_paid.checked_add(opening_fee).ok_or(ErrorCode::MathOverflow)?;
+        self.realized_pnl = self.realized_pnl
+            .checked_sub(i64::try_from(opening_fee).map_err(|_| ErrorCode::MathOverflow)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    // A settled position is no longer open, and wins when it pays out more than its stake
+    pub fn record_settlement(&mut self, position_type: PositionType, stake: u64, is_win: bool) -> Result<()> {
+        self.open_positions = self.open_positions.saturating_sub(1);
+        self.open_stake = self.open_stake.saturating_sub(stake);
+
+        let outcomes = match (position_type, is_win) {
+            (PositionType::StayIn, true) => &mut self.stay_in_wins,
+            (PositionType::StayIn, false) => &mut self.stay_in_losses,
+            (PositionType::Breakout, true) => &mut self.breakout_wins,
+            (PositionType::Breakout, false) => &mut self.breakout_losses,
+        };
+        *outcomes = outcomes.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn record_claim(&mut self, stake: u64, user_payout: u64, winnings_fee: u64) -> Result<()> {
+        self.total_paid_out = self.total_paid_out.checked_add(user_payout).ok_or(ErrorCode::MathOverflow)?;
+        self.total_fees_paid = self.total_fees_paid.checked_add(winnings_fee).ok_or(ErrorCode::MathOverflow)?;
+
+        let pnl = user_payout as i128 - stake as i128;
+        self.realized_pnl = i64::try_from(self.realized_pnl as i128 + pnl)
+            .map_err(|_| ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
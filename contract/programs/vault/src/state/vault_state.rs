@@ -1,13 +1,84 @@
 use anchor_lang::prelude::*;
 
+use crate::error::ErrorCode;
+use crate::state::TradingPool;
+
 #[account]
 #[derive(InitSpace)]
 pub struct VaultState {
+    pub version: u8,
     pub authority: Pubkey,
+    // Id the next position opened from this vault will get
+    pub next_position_id: u64,
+    // Pool positions opened from this vault and not yet claimed
+    pub open_positions: u32,
+    pub open_notional: u64,
+    pub open_max_payout: u64,
     pub vault_bump: u8,
     pub state_bump: u8,
+    pub reserved: [u8; 64],
 }
 
 impl VaultState {
+    pub const VERSION: u8 = 1;
+
     pub const MIN_ORDER_AMOUNT: u64 = 100_000_000; 
+
+    pub fn take_position_id(&mut self) -> Result<u64> {
+        let position_id = self.next_position_id;
+        self.next_position_id = position_id.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(position_id)
+    }
+
+    // Count a new pool position against the market's per-user caps, a cap of zero means no limit
+    pub fn record_open(&mut self, stake: u64, max_payout: u64, trading_pool: &TradingPool) -> Result<()> {
+        let open_positions = self.open_positions.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        let open_notional = self.open_notional.checked_add(stake).ok_or(ErrorCode::MathOverflow)?;
+        let open_max_payout = self.open_max_payout.checked_add(max_payout).ok_or(ErrorCode::MathOverflow)?;
+
+        require!(
+            trading_pool.max_user_open_positions == 0
+                || open_positions <= trading_pool.max_user_open_positions,
+            ErrorCode::UserPositionLimitExceeded
+        );
+        require!(
+            trading_pool.max_user_notional == 0
+                || open_notional <= trading_pool.max_user_notional,
+            ErrorCode::UserNotionalLimitExceeded
+        );
+        require!(
+            trading_pool.max_user_payout == 0
+                || open_max_payout <= trading_pool.max_user_payout,
+            ErrorCode::UserPayoutLimitExceeded
+        );
+
+        self.open_positions = open_positions;
+        self.open_notional = open_notional;
+        self.open_max_payout = open_max_payout;
+
+        Ok(())
+    }
+
+    // Count a position opened before versioning. It was never held to the caps, and
+    // its id may be past the counter since ids were chosen by the client
+    pub fn record_migrated(&mut self, position_id: u64, stake: u64, max_payout: u64, is_open: bool) -> Result<()> {
+        let next_position_id = position_id.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.next_position_id = self.next_position_id.max(next_position_id);
+
+        if is_open {
+            self.open_positions = self.open_positions.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+            self.open_notional = self.open_notional.checked_add(stake).ok_or(ErrorCode::MathOverflow)?;
+            self.open_max_payout = self.open_max_payout.checked_add(max_payout).ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        Ok(())
+    }
+
+    // Release a claimed pool position from the open totals
+    pub fn record_close(&mut self, stake: u64, max_payout: u64) {
+        self.open_positions = self.open_positions.saturating_sub(1);
+        self.open_notional = self.open_notional.saturating_sub(stake);
+        self.open_max_payout = self.open_max_payout.saturating_sub(max_payout);
+    }
 }
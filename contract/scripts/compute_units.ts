@@ -0,0 +1,154 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, TransactionInstruction, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { HermesClient } from "@pythnetwork/hermes-client";
+import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
+
+// Measures the compute units the program consumes for create_position,
+// check_position and claim_position. Needs a live Pyth receiver, so it runs
+// against devnet with `yarn bench:cu` rather than as part of `anchor test`.
+// The wallet must hold the keeper role for check_position. Set
+// SETTLED_POSITION_ID to the id of one of the wallet's settled positions to
+// also measure claim_position.
+
+const BTC_FEED_ID = "0xe62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43";
+
+describe("compute units", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const user = provider.wallet.publicKey;
+  const hermes = new HermesClient("https://hermes.pyth.network/", {});
+  const receiver = new PythSolanaReceiver({
+    connection: provider.connection,
+    wallet: provider.wallet as anchor.Wallet,
+  });
+
+  const results: Record<string, number> = {};
+
+  const [vaultState] = PublicKey.findProgramAddressSync(
+    [Buffer.from("vault_state"), user.toBuffer()],
+    program.programId
+  );
+  const [vault] = PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), vaultState.toBuffer()],
+    program.programId
+  );
+
+  const positionPda = (positionId: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), user.toBuffer(), positionId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  // Index pages cover 32 position ids each
+  const positionIndexPda = (positionId: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("position_index"), user.toBuffer(), positionId.divn(32).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  // Units reported by the runtime for this program's top-level invocation
+  const programUnits = async (signature: string): Promise<number | undefined> => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const pattern = new RegExp(`^Program ${program.programId.toBase58()} consumed (\\d+) of`);
+    for (const log of tx?.meta?.logMessages ?? []) {
+      const match = log.match(pattern);
+      if (match) {
+        return Number(match[1]);
+      }
+    }
+    return undefined;
+  };
+
+  // Posts a fresh price update, runs the instruction against it and closes the update account
+  const measureWithPrice = async (
+    label: string,
+    buildInstruction: (priceUpdate: PublicKey) => Promise<TransactionInstruction>
+  ) => {
+    const updates = await hermes.getLatestPriceUpdates([BTC_FEED_ID], { encoding: "base64" });
+    const builder = receiver.newTransactionBuilder({ closeUpdateAccounts: true });
+    await builder.addPostPriceUpdates(updates.binary.data);
+    await builder.addPriceConsumerInstructions(async (getPriceUpdateAccount) => [
+      { instruction: await buildInstruction(getPriceUpdateAccount(BTC_FEED_ID)), signers: [] },
+    ]);
+
+    const transactions = await builder.buildVersionedTransactions({
+      computeUnitPriceMicroLamports: 50_000,
+    });
+    const signatures = await receiver.provider.sendAll(transactions, { skipPreflight: true });
+    for (const signature of signatures) {
+      const units = await programUnits(signature);
+      if (units !== undefined) {
+        results[label] = units;
+      }
+    }
+  };
+
+  before(async () => {
+    if (!(await provider.connection.getAccountInfo(vaultState))) {
+      await program.methods.initialize().accountsPartial({ user, vaultState, vault }).rpc();
+    }
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL / 5), new anchor.BN(Date.now()))
+      .accountsPartial({ user, vault, vaultState })
+      .rpc();
+  });
+
+  it("create_position and check_position", async () => {
+    const { nextPositionId } = await program.account.vaultState.fetch(vaultState);
+    const position = positionPda(nextPositionId);
+    const positionIndex = positionIndexPda(nextPositionId);
+
+    const { price } = (await hermes.getLatestPriceUpdates([BTC_FEED_ID])).parsed[0].price;
+    const current = new anchor.BN(price);
+    const width = current.divn(50);
+
+    await measureWithPrice("create_position", (priceUpdate) =>
+      program.methods
+        .createPosition({ stayIn: {} }, current.sub(width), current.add(width), new anchor.BN(LAMPORTS_PER_SOL / 5), false)
+        .accountsPartial({
+          user,
+          position,
+          positionIndex,
+          userVault: vault,
+          userVaultState: vaultState,
+          referrer: null,
+          priceUpdate,
+        })
+        .instruction()
+    );
+
+    // The position is neither expired nor out of range, so this is the common no-op check
+    await measureWithPrice("check_position", (priceUpdate) =>
+      program.methods
+        .checkPosition()
+        .accountsPartial({ keeper: user, user, position, priceUpdate })
+        .instruction()
+    );
+  });
+
+  it("claim_position", async function () {
+    if (!process.env.SETTLED_POSITION_ID) {
+      this.skip();
+    }
+    const positionId = new anchor.BN(process.env.SETTLED_POSITION_ID);
+    const position = positionPda(positionId);
+    const positionIndex = positionIndexPda(positionId);
+
+    const signature = await program.methods
+      .claimPosition()
+      .accountsPartial({ user, position, positionIndex, userVault: vault, userVaultState: vaultState })
+      .rpc({ commitment: "confirmed" });
+    results["claim_position"] = await programUnits(signature);
+  });
+
+  after(() => {
+    console.table(results);
+  });
+});
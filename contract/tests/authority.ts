@@ -0,0 +1,202 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { Keypair, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import { expectError, pda, protocolAccounts, u64Seed } from "./helpers";
+
+describe("authority transfer and timelock", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const admin = provider.wallet.publicKey;
+  const { protocolConfig, tradingPool } = protocolAccounts(program);
+  const successor = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  // Default delay set by init_protocol_config
+  const timelockDelay = 24 * 60 * 60;
+
+  before(async () => {
+    for (const wallet of [successor, outsider]) {
+      const airdropTx = await provider.connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(airdropTx);
+    }
+  });
+
+  describe("protocol authority", () => {
+    it("Only the current authority proposes a successor", async () => {
+      await expectError(
+        program.methods
+          .proposeProtocolAuthority(outsider.publicKey)
+          .accounts({ authority: outsider.publicKey, protocolConfig })
+          .signers([outsider])
+          .rpc(),
+        "UnauthorizedAccess"
+      );
+    });
+
+    it("Keeps the authority until the proposed successor accepts", async () => {
+      await program.methods
+        .proposeProtocolAuthority(successor.publicKey)
+        .accounts({ authority: admin, protocolConfig })
+        .rpc();
+
+      const proposed = await program.account.protocolConfig.fetch(protocolConfig);
+      expect(proposed.authority.toBase58()).to.equal(admin.toBase58());
+      expect(proposed.pendingAuthority.toBase58()).to.equal(successor.publicKey.toBase58());
+
+      await expectError(
+        program.methods
+          .acceptProtocolAuthority()
+          .accounts({ newAuthority: outsider.publicKey, protocolConfig })
+          .signers([outsider])
+          .rpc(),
+        "NoPendingAuthority"
+      );
+    });
+
+    it("Hands over the authority on acceptance, and back again", async () => {
+      await program.methods
+        .acceptProtocolAuthority()
+        .accounts({ newAuthority: successor.publicKey, protocolConfig })
+        .signers([successor])
+        .rpc();
+
+      const transferred = await program.account.protocolConfig.fetch(protocolConfig);
+      expect(transferred.authority.toBase58()).to.equal(successor.publicKey.toBase58());
+      expect(transferred.pendingAuthority).to.be.null;
+
+      // The old authority lost its rights, later suites need them back
+      await expectError(
+        program.methods.proposeProtocolAuthority(admin).accounts({ authority: admin, protocolConfig }).rpc(),
+        "UnauthorizedAccess"
+      );
+      await program.methods
+        .proposeProtocolAuthority(admin)
+        .accounts({ authority: successor.publicKey, protocolConfig })
+        .signers([successor])
+        .rpc();
+      await program.methods.acceptProtocolAuthority().accounts({ newAuthority: admin, protocolConfig }).rpc();
+
+      const restored = await program.account.protocolConfig.fetch(protocolConfig);
+      expect(restored.authority.toBase58()).to.equal(admin.toBase58());
+    });
+  });
+
+  describe("pool authority", () => {
+    it("Hands over the pool authority only to the proposed successor", async () => {
+      await expectError(
+        program.methods
+          .proposePoolAuthority(outsider.publicKey)
+          .accounts({ authority: outsider.publicKey, tradingPool })
+          .signers([outsider])
+          .rpc(),
+        "UnauthorizedAccess"
+      );
+
+      await program.methods.proposePoolAuthority(successor.publicKey).accounts({ authority: admin, tradingPool }).rpc();
+      await expectError(
+        program.methods
+          .acceptPoolAuthority()
+          .accounts({ newAuthority: outsider.publicKey, tradingPool })
+          .signers([outsider])
+          .rpc(),
+        "NoPendingAuthority"
+      );
+
+      await program.methods
+        .acceptPoolAuthority()
+        .accounts({ newAuthority: successor.publicKey, tradingPool })
+        .signers([successor])
+        .rpc();
+      const transferred = await program.account.tradingPool.fetch(tradingPool);
+      expect(transferred.authority.toBase58()).to.equal(successor.publicKey.toBase58());
+      expect(transferred.pendingAuthority).to.be.null;
+
+      await program.methods
+        .proposePoolAuthority(admin)
+        .accounts({ authority: successor.publicKey, tradingPool })
+        .signers([successor])
+        .rpc();
+      await program.methods.acceptPoolAuthority().accounts({ newAuthority: admin, tradingPool }).rpc();
+      const restored = await program.account.tradingPool.fetch(tradingPool);
+      expect(restored.authority.toBase58()).to.equal(admin.toBase58());
+    });
+  });
+
+  // Executing at and after the ETA is covered by the QueuedChange unit tests, since
+  // the local validator cannot move its clock a day ahead
+  describe("timelocked config changes", () => {
+    let queuedChange: PublicKey;
+    const change = { positionDuration: { duration: new anchor.BN(2 * 60 * 60) } };
+
+    it("Only the authority queues a change", async () => {
+      const { nextChangeId } = await program.account.protocolConfig.fetch(protocolConfig);
+
+      await expectError(
+        program.methods
+          .queueConfigChange(change)
+          .accounts({
+            authority: outsider.publicKey,
+            protocolConfig,
+            queuedChange: pda(program, Buffer.from("queued_change"), protocolConfig.toBuffer(), u64Seed(nextChangeId)),
+          })
+          .signers([outsider])
+          .rpc(),
+        "UnauthorizedAccess"
+      );
+    });
+
+    it("Queues a change to execute once the delay has passed", async () => {
+      const { nextChangeId } = await program.account.protocolConfig.fetch(protocolConfig);
+      queuedChange = pda(program, Buffer.from("queued_change"), protocolConfig.toBuffer(), u64Seed(nextChangeId));
+
+      await program.methods.queueConfigChange(change).accounts({ authority: admin, protocolConfig, queuedChange }).rpc();
+
+      const queued = await program.account.queuedChange.fetch(queuedChange);
+      expect(queued.changeId.toString()).to.equal(nextChangeId.toString());
+      expect(queued.proposer.toBase58()).to.equal(admin.toBase58());
+      expect(queued.executableAt.sub(queued.queuedAt).toNumber()).to.equal(timelockDelay);
+
+      const config = await program.account.protocolConfig.fetch(protocolConfig);
+      expect(config.nextChangeId.toString()).to.equal(nextChangeId.addn(1).toString());
+    });
+
+    it("Rejects executing the change before its ETA", async () => {
+      await expectError(
+        program.methods
+          .executeConfigChange()
+          .accounts({ authority: admin, protocolConfig, queuedChange, proposer: admin })
+          .rpc(),
+        "TimelockNotExpired"
+      );
+
+      const config = await program.account.protocolConfig.fetch(protocolConfig);
+      expect(config.positionDuration.toNumber()).to.equal(86400);
+    });
+
+    it("Cancels the change, refunding its rent to the proposer", async () => {
+      await expectError(
+        program.methods
+          .cancelConfigChange()
+          .accounts({ authority: outsider.publicKey, protocolConfig, queuedChange, proposer: admin })
+          .signers([outsider])
+          .rpc(),
+        "UnauthorizedAccess"
+      );
+
+      const rent = await provider.connection.getBalance(queuedChange);
+      const adminBefore = await provider.connection.getBalance(admin);
+
+      await program.methods
+        .cancelConfigChange()
+        .accounts({ authority: admin, protocolConfig, queuedChange, proposer: admin })
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(queuedChange)).to.be.null;
+      expect((await provider.connection.getBalance(admin)) - adminBefore).to.equal(rent - 5000);
+    });
+  });
+});
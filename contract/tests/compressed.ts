@@ -0,0 +1,217 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import { createHash } from "crypto";
+import {
+  ensureKeeper,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  pda,
+  priceInBand,
+  priceOutOfBand,
+  protocolAccounts,
+  u64Seed,
+} from "./helpers";
+
+const treeDepth = 14;
+
+function hashPair(left: Buffer, right: Buffer): Buffer {
+  return createHash("sha256").update(left).update(right).digest();
+}
+
+// Every node of each level of a tree built from `leaves`, padded with empty leaves
+function treeLevels(leaves: Buffer[]): Buffer[][] {
+  const levels = [leaves];
+  let empty = Buffer.alloc(32);
+  for (let level = 0; level < treeDepth; level++) {
+    const nodes = levels[level];
+    const parents: Buffer[] = [];
+    for (let index = 0; index < Math.max(nodes.length, 1); index += 2) {
+      parents.push(hashPair(nodes[index] ?? empty, nodes[index + 1] ?? empty));
+    }
+    levels.push(parents);
+    empty = hashPair(empty, empty);
+  }
+  return levels;
+}
+
+function rootOf(leaves: Buffer[]): number[] {
+  return Array.from(treeLevels(leaves)[treeDepth][0]);
+}
+
+function proofFor(leaves: Buffer[], index: number): number[][] {
+  const levels = treeLevels(leaves);
+  const proof: number[][] = [];
+  let empty = Buffer.alloc(32);
+  for (let level = 0; level < treeDepth; level++) {
+    proof.push(Array.from(levels[level][(index >> level) ^ 1] ?? empty));
+    empty = hashPair(empty, empty);
+  }
+  return proof;
+}
+
+describe("compressed positions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const keeper = provider.wallet.publicKey;
+  const { protocolConfig, roles, treasury, tradingPool, tradingPoolVault } = protocolAccounts(program);
+  const amount = new anchor.BN(LAMPORTS_PER_SOL / 2);
+
+  // pool.ts creates tree 0, this suite keeps its own tree so leaf indexes start at 0
+  const treeId = new anchor.BN(1);
+  const positionTree = pda(program, Buffer.from("position_tree"), tradingPool.toBuffer(), u64Seed(treeId));
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+  let exposureBook: PublicKey;
+  let userStats: PublicKey;
+
+  // Leaves as the program hashed them, and the positions behind them
+  const positions = [];
+  const leaves: Buffer[] = [];
+
+  function leafOf(position): Buffer {
+    return createHash("sha256").update(program.coder.types.encode("compressedPosition", position)).digest();
+  }
+
+  // Compressed positions only exist in the program's events, which clients index
+  async function positionFromEvent(signature: string, eventName: string) {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const event = [...parser.parseLogs(tx.meta.logMessages)].find((event) => event.name === eventName);
+    return event.data.position;
+  }
+
+  async function createCompressed() {
+    const signature = await program.methods
+      .createCompressedPosition({ breakout: {} }, new anchor.BN(60000), new anchor.BN(70000), amount)
+      .accounts({
+        user: owner.user.publicKey,
+        positionTree,
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        tradingPool,
+        tradingPoolVault,
+        protocolConfig,
+        treasury,
+        userStats,
+        referrer: null,
+        exposureBook,
+        priceUpdate: priceInBand,
+      })
+      .signers([owner.user])
+      .rpc({ commitment: "confirmed" });
+
+    const position = await positionFromEvent(signature, "compressedPositionCreatedEvent");
+    positions.push(position);
+    leaves.push(leafOf(position));
+  }
+
+  function settleCompressed(root: number[], position, proof: number[][]) {
+    return program.methods
+      .settleCompressedPosition(root, position, proof)
+      .accounts({
+        keeper,
+        positionTree,
+        tradingPool,
+        exposureBook,
+        protocolConfig,
+        roles,
+        priceUpdate: priceOutOfBand,
+        userStats,
+      });
+  }
+
+  function claimCompressed(root: number[], position, proof: number[][]) {
+    return program.methods
+      .claimCompressedPosition(root, position, proof)
+      .accounts({
+        user: owner.user.publicKey,
+        positionTree,
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        tradingPool,
+        tradingPoolVault,
+        protocolConfig,
+        treasury,
+        userStats,
+      })
+      .signers([owner.user]);
+  }
+
+  before(async () => {
+    await ensureKeeper(program, keeper);
+    exposureBook = await ensurePositionMarket(program);
+    owner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    userStats = pda(program, Buffer.from("user_stats"), owner.user.publicKey.toBuffer());
+
+    await program.methods
+      .initPositionTree(treeId)
+      .accounts({ authority: keeper, tradingPool, positionTree })
+      .rpc();
+  });
+
+  it("Appends each new position as a leaf of the tree", async () => {
+    await createCompressed();
+    await createCompressed();
+
+    expect(positions.map((position) => position.leafIndex.toNumber())).to.deep.equal([0, 1]);
+
+    const tree = await program.account.positionTree.fetch(positionTree);
+    expect(tree.nextLeafIndex.toNumber()).to.equal(2);
+    expect(tree.changeLogs[tree.activeIndex.toNumber()].root).to.deep.equal(rootOf(leaves));
+  });
+
+  it("Rejects settling with a proof for another leaf", async () => {
+    await expectError(
+      settleCompressed(rootOf(leaves), positions[0], proofFor(leaves, 1)).rpc(),
+      "InvalidMerkleProof"
+    );
+  });
+
+  it("Settles a position with a proof against an earlier root", async () => {
+    // Root and proof from before the second position was appended
+    const earlierLeaves = leaves.slice(0, 1);
+    const signature = await settleCompressed(rootOf(earlierLeaves), positions[0], proofFor(earlierLeaves, 0))
+      .rpc({ commitment: "confirmed" });
+
+    const settled = await positionFromEvent(signature, "compressedPositionSettledEvent");
+    expect(settled.status).to.deep.equal({ settled: {} });
+    expect(settled.settlementData.payoutPercentage).to.be.greaterThan(100);
+    positions[0] = settled;
+    leaves[0] = leafOf(settled);
+
+    const tree = await program.account.positionTree.fetch(positionTree);
+    expect(tree.changeLogs[tree.activeIndex.toNumber()].root).to.deep.equal(rootOf(leaves));
+  });
+
+  it("Rejects claiming a position that has not settled", async () => {
+    await expectError(
+      claimCompressed(rootOf(leaves), positions[1], proofFor(leaves, 1)).rpc(),
+      "PositionNotSettled"
+    );
+  });
+
+  it("Pays out a settled position once", async () => {
+    const claimedLeaves = [...leaves];
+    const vaultBefore = await provider.connection.getBalance(owner.vault);
+
+    await claimCompressed(rootOf(leaves), positions[0], proofFor(leaves, 0)).rpc();
+
+    const vaultAfter = await provider.connection.getBalance(owner.vault);
+    expect(vaultAfter).to.be.greaterThan(vaultBefore);
+
+    // The leaf now holds the claimed position, so the settled one no longer proves
+    await expectError(
+      claimCompressed(rootOf(claimedLeaves), positions[0], proofFor(claimedLeaves, 0)).rpc(),
+      "InvalidMerkleProof"
+    );
+  });
+});
@@ -0,0 +1,186 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  ensureKeeper,
+  expectError,
+  fundedUser,
+  inBandPrice,
+  pda,
+  priceInBand,
+  priceOutOfBand,
+  protocolAccounts,
+  u64Seed,
+} from "./helpers";
+
+describe("parimutuel epochs", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const keeper = provider.wallet.publicKey;
+  const { protocolConfig, roles, treasury, tradingPool } = protocolAccounts(program);
+
+  const epochLength = 86400;
+  const bandWidthBps = 1000;
+  const stake = LAMPORTS_PER_SOL / 2;
+
+  let market: PublicKey;
+  let epochIndex: number;
+  let epoch: PublicKey;
+  let epochVault: PublicKey;
+  let staker: Awaited<ReturnType<typeof fundedUser>>;
+
+  before(async () => {
+    await ensureKeeper(program, keeper);
+    staker = await fundedUser(program, LAMPORTS_PER_SOL);
+
+    market = pda(program, Buffer.from("parimutuel_market"), tradingPool.toBuffer());
+
+    // Epochs are aligned to the unix epoch, so the current one follows from the clock
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    epochIndex = Math.floor(now / epochLength);
+    epoch = pda(program, Buffer.from("epoch"), market.toBuffer(), u64Seed(epochIndex));
+    epochVault = pda(program, Buffer.from("epoch_vault"), epoch.toBuffer());
+
+    await program.methods
+      .initParimutuelMarket(new anchor.BN(epochLength), new anchor.BN(epochLength - 1), bandWidthBps)
+      .accounts({ authority: keeper, tradingPool, market })
+      .rpc();
+  });
+
+  it("Rejects opening an epoch other than the current one", async () => {
+    const nextEpoch = pda(program, Buffer.from("epoch"), market.toBuffer(), u64Seed(epochIndex + 1));
+
+    await expectError(
+      program.methods
+        .openEpoch(new anchor.BN(epochIndex + 1))
+        .accounts({
+          keeper,
+          market,
+          epoch: nextEpoch,
+          epochVault: pda(program, Buffer.from("epoch_vault"), nextEpoch.toBuffer()),
+          protocolConfig,
+          roles,
+          priceUpdate: priceInBand,
+        })
+        .rpc(),
+      "InvalidEpoch"
+    );
+  });
+
+  it("Opens the current epoch with a band around the oracle price", async () => {
+    await program.methods
+      .openEpoch(new anchor.BN(epochIndex))
+      .accounts({ keeper, market, epoch, epochVault, protocolConfig, roles, priceUpdate: priceInBand })
+      .rpc();
+
+    const opened = await program.account.epoch.fetch(epoch);
+    expect(opened.startTime.toNumber()).to.equal(epochIndex * epochLength);
+    expect(opened.endTime.toNumber()).to.equal((epochIndex + 1) * epochLength);
+    expect(opened.openPrice.toNumber()).to.equal(inBandPrice);
+    expect(opened.lowerBound.toNumber()).to.equal(inBandPrice * 0.9);
+    expect(opened.upperBound.toNumber()).to.equal(inBandPrice * 1.1);
+    expect(opened.status).to.deep.equal({ open: {} });
+  });
+
+  it("Rejects stakes once the price has left the band", async () => {
+    await expectError(
+      program.methods
+        .stakeEpoch({ stayIn: {} }, new anchor.BN(stake))
+        .accounts({
+          user: staker.user.publicKey,
+          market,
+          epoch,
+          epochVault,
+          userVault: staker.vault,
+          userVaultState: staker.vaultState,
+          protocolConfig,
+          priceUpdate: priceOutOfBand,
+        })
+        .signers([staker.user])
+        .rpc(),
+      "EpochBandBroken"
+    );
+  });
+
+  it("Stakes on a side while the price is in the band", async () => {
+    await program.methods
+      .stakeEpoch({ stayIn: {} }, new anchor.BN(stake))
+      .accounts({
+        user: staker.user.publicKey,
+        market,
+        epoch,
+        epochVault,
+        userVault: staker.vault,
+        userVaultState: staker.vaultState,
+        protocolConfig,
+        priceUpdate: priceInBand,
+      })
+      .signers([staker.user])
+      .rpc();
+
+    const staked = await program.account.epoch.fetch(epoch);
+    expect(staked.stayInTotal.toNumber()).to.equal(stake);
+    expect(staked.breakoutTotal.toNumber()).to.equal(0);
+
+    const epochStake = await program.account.epochStake.fetch(
+      pda(program, Buffer.from("epoch_stake"), epoch.toBuffer(), staker.user.publicKey.toBuffer())
+    );
+    expect(epochStake.stayInAmount.toNumber()).to.equal(stake);
+  });
+
+  it("Only a keeper can settle an epoch", async () => {
+    await expectError(
+      program.methods
+        .settleEpoch()
+        .accounts({
+          keeper: staker.user.publicKey,
+          market,
+          epoch,
+          epochVault,
+          protocolConfig,
+          treasury,
+          roles,
+          priceUpdate: priceInBand,
+        })
+        .signers([staker.user])
+        .rpc(),
+      "UnauthorizedAccess"
+    );
+  });
+
+  it("Settles on a price published after the epoch and refunds a one-sided pot", async () => {
+    await program.methods
+      .settleEpoch()
+      .accounts({ keeper, market, epoch, epochVault, protocolConfig, treasury, roles, priceUpdate: priceInBand })
+      .rpc();
+
+    // Nobody took the Breakout side, so there is nothing to win and no fee
+    const settled = await program.account.epoch.fetch(epoch);
+    expect(settled.status).to.deep.equal({ settled: {} });
+    expect(settled.winningSide).to.be.null;
+    expect(settled.settlementPrice.toNumber()).to.equal(inBandPrice);
+    expect(settled.fee.toNumber()).to.equal(0);
+
+    const vaultBefore = await provider.connection.getBalance(staker.vault);
+    await program.methods
+      .claimEpoch()
+      .accounts({
+        user: staker.user.publicKey,
+        market,
+        epoch,
+        epochVault,
+        epochStake: pda(program, Buffer.from("epoch_stake"), epoch.toBuffer(), staker.user.publicKey.toBuffer()),
+        userVault: staker.vault,
+        userVaultState: staker.vaultState,
+      })
+      .signers([staker.user])
+      .rpc();
+
+    const vaultAfter = await provider.connection.getBalance(staker.vault);
+    expect(vaultAfter - vaultBefore).to.equal(stake);
+  });
+});
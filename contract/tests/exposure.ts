@@ -0,0 +1,113 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  checkPosition,
+  ensureKeeper,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  openPosition,
+  priceOutOfBand,
+  protocolAccounts,
+} from "./helpers";
+
+describe("exposure limits", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const admin = provider.wallet.publicKey;
+  const { protocolConfig, roles } = protocolAccounts(program);
+  const amount = LAMPORTS_PER_SOL / 2;
+
+  // A band inside one bucket no other suite uses: the book starts at 50000 with
+  // 1000 wide buckets, so this is bucket 30
+  const lowerBound = 80100;
+  const upperBound = 80900;
+  const bucket = 30;
+  // Set by ensurePositionMarket, restored after each test that changes it
+  const exposureLimit = new anchor.BN(100 * LAMPORTS_PER_SOL);
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+  let exposureBook: PublicKey;
+
+  function setExposureLimit(maxNetExposure: anchor.BN) {
+    return program.methods
+      .updateExposureLimit(maxNetExposure)
+      .accounts({ riskManager: admin, exposureBook, protocolConfig, roles })
+      .rpc();
+  }
+
+  async function bucketExposure() {
+    const book = await program.account.exposureBook.fetch(exposureBook);
+    return { stayIn: book.stayInExposure[bucket], breakout: book.breakoutExposure[bucket] };
+  }
+
+  before(async () => {
+    await ensureKeeper(program, admin);
+    exposureBook = await ensurePositionMarket(program);
+    owner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+  });
+
+  afterEach(async () => {
+    await setExposureLimit(exposureLimit);
+  });
+
+  it("Only the risk manager updates the exposure limit", async () => {
+    await expectError(
+      program.methods
+        .updateExposureLimit(exposureLimit)
+        .accounts({ riskManager: owner.user.publicKey, exposureBook, protocolConfig, roles })
+        .signers([owner.user])
+        .rpc(),
+      "UnauthorizedAccess"
+    );
+  });
+
+  it("Rejects a position that takes a bucket's net exposure over the limit", async () => {
+    await setExposureLimit(new anchor.BN(LAMPORTS_PER_SOL / 10));
+    const before = await bucketExposure();
+
+    await expectError(
+      openPosition(program, owner, { stayIn: {} }, amount, lowerBound, upperBound),
+      "ExposureLimitExceeded"
+    );
+
+    const after = await bucketExposure();
+    expect(after.stayIn.toString()).to.equal(before.stayIn.toString());
+    expect(after.breakout.toString()).to.equal(before.breakout.toString());
+  });
+
+  let stayIn: PublicKey;
+  let stayInStake: anchor.BN;
+
+  it("Accepts a hedging position even while the bucket is over the limit", async () => {
+    ({ position: stayIn } = await openPosition(program, owner, { stayIn: {} }, amount, lowerBound, upperBound));
+    stayInStake = (await program.account.positionState.fetch(stayIn)).amount;
+
+    // The bucket now holds one StayIn stake, so a limit below it leaves the bucket over.
+    // A Breakout stake of the same size brings its net back down and is still accepted
+    await setExposureLimit(new anchor.BN(LAMPORTS_PER_SOL / 10));
+    const before = await bucketExposure();
+
+    const { position: breakout } = await openPosition(program, owner, { breakout: {} }, amount, lowerBound, upperBound);
+
+    const breakoutStake = (await program.account.positionState.fetch(breakout)).amount;
+    const after = await bucketExposure();
+    expect(after.breakout.sub(before.breakout).toString()).to.equal(breakoutStake.toString());
+  });
+
+  it("Releases a position's exposure when it settles", async () => {
+    const before = await bucketExposure();
+
+    // 75000 is outside the band, so the StayIn position settles
+    await checkPosition(program, owner.user.publicKey, stayIn, priceOutOfBand);
+
+    const after = await bucketExposure();
+    expect(before.stayIn.sub(after.stayIn).toString()).to.equal(stayInStake.toString());
+    expect(after.breakout.toString()).to.equal(before.breakout.toString());
+  });
+});
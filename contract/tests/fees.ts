@@ -0,0 +1,137 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { Keypair, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  checkPosition,
+  claimPosition,
+  ensureKeeper,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  openPosition,
+  pda,
+  priceOutOfBand,
+  protocolAccounts,
+} from "./helpers";
+
+describe("protocol fees", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const admin = provider.wallet.publicKey;
+  const { protocolConfig, roles, treasury } = protocolAccounts(program);
+  const amount = new anchor.BN(LAMPORTS_PER_SOL);
+
+  // Set by pool.ts: 0.3% opening fee, 5% winnings fee, 20% of opening fees to referrers
+  const openingFeeBps = 30;
+  const winningsFeeBps = 500;
+  const referralShareBps = 2000;
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+  const referrerAuthority = Keypair.generate();
+  let referrer: PublicKey;
+  let position: PublicKey;
+
+  function bps(value: anchor.BN, basisPoints: number) {
+    return value.muln(basisPoints).divn(10000);
+  }
+
+  function withdrawFees(feeCollector: Keypair | null, withdrawAmount: anchor.BN) {
+    const builder = program.methods.withdrawFees(withdrawAmount).accounts({
+      feeCollector: feeCollector?.publicKey ?? admin,
+      protocolConfig,
+      roles,
+      treasury,
+      recipient: admin,
+    });
+    return (feeCollector ? builder.signers([feeCollector]) : builder).rpc();
+  }
+
+  before(async () => {
+    await ensureKeeper(program, admin);
+    await ensurePositionMarket(program);
+    owner = await fundedUser(program, 5 * LAMPORTS_PER_SOL);
+
+    const airdropTx = await provider.connection.requestAirdrop(referrerAuthority.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(airdropTx);
+    referrer = pda(program, Buffer.from("referrer"), referrerAuthority.publicKey.toBuffer());
+    await program.methods
+      .registerReferrer()
+      .accounts({ authority: referrerAuthority.publicKey, referrer })
+      .signers([referrerAuthority])
+      .rpc();
+
+    const current = await program.account.roles.fetch(roles);
+    if (!current.feeCollectors.some((member) => member.equals(admin))) {
+      await program.methods
+        .grantRole({ feeCollector: {} }, admin)
+        .accounts({ authority: admin, protocolConfig, roles })
+        .rpc();
+    }
+  });
+
+  it("Takes the opening fee out of the amount and sends it to the treasury", async () => {
+    const treasuryBefore = await provider.connection.getBalance(treasury);
+    const vaultBefore = await provider.connection.getBalance(owner.vault);
+    const configBefore = await program.account.protocolConfig.fetch(protocolConfig);
+
+    ({ position } = await openPosition(program, owner, { breakout: {} }, amount.toNumber(), 60000, 70000, referrer));
+
+    const openingFee = bps(amount, openingFeeBps);
+    const rebate = bps(openingFee, referralShareBps);
+    const opened = await program.account.positionState.fetch(position);
+    expect(opened.amount.toString()).to.equal(amount.sub(openingFee).toString());
+
+    // The vault pays the stake and the fee, the treasury holds the whole fee including the rebate
+    expect(vaultBefore - (await provider.connection.getBalance(owner.vault))).to.equal(amount.toNumber());
+    expect((await provider.connection.getBalance(treasury)) - treasuryBefore).to.equal(openingFee.toNumber());
+
+    const configAfter = await program.account.protocolConfig.fetch(protocolConfig);
+    expect(configAfter.totalFeesCollected.sub(configBefore.totalFeesCollected).toString()).to.equal(
+      openingFee.toString()
+    );
+    expect(configAfter.totalReferralOwed.sub(configBefore.totalReferralOwed).toString()).to.equal(rebate.toString());
+  });
+
+  it("Charges the winnings fee on profit only, paid to the treasury on claim", async () => {
+    await checkPosition(program, owner.user.publicKey, position, priceOutOfBand);
+    const settled = await program.account.positionState.fetch(position);
+    expect(settled.payoutPercentage).to.be.greaterThan(100);
+
+    const payout = settled.amount.muln(settled.payoutPercentage).divn(100);
+    const winningsFee = bps(payout.sub(settled.amount), winningsFeeBps);
+
+    const treasuryBefore = await provider.connection.getBalance(treasury);
+    const vaultBefore = await provider.connection.getBalance(owner.vault);
+
+    await claimPosition(program, owner, position);
+
+    expect((await provider.connection.getBalance(treasury)) - treasuryBefore).to.equal(winningsFee.toNumber());
+    expect((await provider.connection.getBalance(owner.vault)) - vaultBefore).to.equal(
+      payout.sub(winningsFee).toNumber()
+    );
+  });
+
+  it("Only a fee collector withdraws fees", async () => {
+    await expectError(withdrawFees(owner.user, new anchor.BN(1)), "UnauthorizedAccess");
+  });
+
+  it("Withdraws fees down to the treasury's rent and the referral rebates owed", async () => {
+    const config = await program.account.protocolConfig.fetch(protocolConfig);
+    expect(config.totalReferralOwed.toNumber()).to.be.greaterThan(0);
+
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+    const available = (await provider.connection.getBalance(treasury)) - rent - config.totalReferralOwed.toNumber();
+
+    await expectError(withdrawFees(null, new anchor.BN(available + 1)), "InsufficientTreasuryBalance");
+
+    await withdrawFees(null, new anchor.BN(available));
+
+    expect(await provider.connection.getBalance(treasury)).to.equal(rent + config.totalReferralOwed.toNumber());
+    const configAfter = await program.account.protocolConfig.fetch(protocolConfig);
+    expect(configAfter.totalFeesWithdrawn.sub(config.totalFeesWithdrawn).toNumber()).to.equal(available);
+  });
+});
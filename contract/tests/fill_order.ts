@@ -0,0 +1,169 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  ensureKeeper,
+  ensureOrderBook,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  indexPageAddress,
+  pda,
+  positionAddress,
+  priceInBand,
+  protocolAccounts,
+  u64Seed,
+} from "./helpers";
+
+describe("order fills", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const admin = provider.wallet.publicKey;
+  const { protocolConfig, roles, treasury, tradingPool, tradingPoolVault } = protocolAccounts(program);
+
+  // Set by pool.ts: 0.3% opening fee and a 0.2 SOL minimum position
+  const openingFeeBps = 30;
+  const minPositionSize = new anchor.BN(0.2 * LAMPORTS_PER_SOL);
+
+  let orderBook: PublicKey;
+  let orderBookVault: PublicKey;
+  let exposureBook: PublicKey;
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+
+  const orderSize = new anchor.BN(LAMPORTS_PER_SOL);
+  let orderId: anchor.BN;
+  let order: PublicKey;
+
+  async function placeOrder(size: anchor.BN) {
+    const { nextOrderId } = await program.account.orderBook.fetch(orderBook);
+    const address = pda(program, Buffer.from("order"), orderBook.toBuffer(), u64Seed(nextOrderId));
+
+    await program.methods
+      .placeOrder(
+        { stayIn: {} },
+        new anchor.BN(60000),
+        new anchor.BN(70000),
+        size,
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+      )
+      .accounts({
+        user: owner.user.publicKey,
+        orderBook,
+        orderBookVault,
+        order: address,
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        protocolConfig,
+      })
+      .signers([owner.user])
+      .rpc();
+
+    return { orderId: nextOrderId, order: address };
+  }
+
+  async function fillOrder(id: anchor.BN, amount: anchor.BN) {
+    const user = owner.user.publicKey;
+    const positionId = (await program.account.vaultState.fetch(owner.vaultState)).nextPositionId.toNumber();
+    const position = positionAddress(program, user, positionId);
+
+    await program.methods
+      .fillOrder(id, amount)
+      .accounts({
+        keeper: admin,
+        user,
+        orderBook,
+        orderBookVault,
+        order: pda(program, Buffer.from("order"), orderBook.toBuffer(), u64Seed(id)),
+        position,
+        userVaultState: owner.vaultState,
+        tradingPool,
+        tradingPoolVault,
+        exposureBook,
+        protocolConfig,
+        treasury,
+        userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+        positionIndex: indexPageAddress(program, user, positionId),
+        roles,
+        priceUpdate: priceInBand,
+      })
+      .rpc();
+
+    return position;
+  }
+
+  function openingFee(amount: anchor.BN) {
+    return amount.muln(openingFeeBps).divn(10000);
+  }
+
+  before(async () => {
+    ({ orderBook, orderBookVault } = await ensureOrderBook(program));
+    exposureBook = await ensurePositionMarket(program);
+    await ensureKeeper(program, admin);
+
+    owner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    ({ orderId, order } = await placeOrder(orderSize));
+  });
+
+  it("Fills part of an order as a pool position", async () => {
+    const fill = new anchor.BN(0.4 * LAMPORTS_PER_SOL);
+    const fee = openingFee(fill);
+    const bookVaultBefore = await provider.connection.getBalance(orderBookVault);
+    const treasuryBefore = await provider.connection.getBalance(treasury);
+
+    const position = await fillOrder(orderId, fill);
+
+    const filled = await program.account.order.fetch(order);
+    expect(filled.filledAmount.toString()).to.equal(fill.toString());
+    expect(filled.status).to.deep.equal({ open: {} });
+
+    const state = await program.account.positionState.fetch(position);
+    expect(state.user.toString()).to.equal(owner.user.publicKey.toString());
+    expect(state.lowerBound.toNumber()).to.equal(60000);
+    expect(state.upperBound.toNumber()).to.equal(70000);
+    expect(state.amount.toString()).to.equal(fill.sub(fee).toString());
+
+    expect(await provider.connection.getBalance(orderBookVault)).to.equal(bookVaultBefore - fill.toNumber());
+    expect(await provider.connection.getBalance(treasury)).to.equal(treasuryBefore + fee.toNumber());
+  });
+
+  it("Fills the remainder and closes the order", async () => {
+    const bookBefore = await program.account.orderBook.fetch(orderBook);
+
+    await fillOrder(orderId, new anchor.BN(0.6 * LAMPORTS_PER_SOL));
+
+    const filled = await program.account.order.fetch(order);
+    expect(filled.filledAmount.toString()).to.equal(orderSize.toString());
+    expect(filled.status).to.deep.equal({ filled: {} });
+
+    const bookAfter = await program.account.orderBook.fetch(orderBook);
+    expect(bookAfter.openOrders.toNumber()).to.equal(bookBefore.openOrders.toNumber() - 1);
+
+    await expectError(fillOrder(orderId, new anchor.BN(0.3 * LAMPORTS_PER_SOL)), "OrderNotOpen");
+  });
+
+  it("Rejects a fill larger than what remains", async () => {
+    const { orderId: id } = await placeOrder(new anchor.BN(0.5 * LAMPORTS_PER_SOL));
+
+    await expectError(fillOrder(id, new anchor.BN(0.6 * LAMPORTS_PER_SOL)), "OrderOverfilled");
+  });
+
+  it("Checks the minimum size against the stake left after the opening fee", async () => {
+    const { orderId: id } = await placeOrder(orderSize);
+
+    // The fill itself meets the minimum, but its stake does not
+    await expectError(fillOrder(id, minPositionSize), "AmountTooSmall");
+
+    const grossedUp = minPositionSize.muln(10000).divn(10000 - openingFeeBps).addn(1);
+    await fillOrder(id, grossedUp);
+  });
+
+  it("Rejects an order id with no order behind it", async () => {
+    const { nextOrderId } = await program.account.orderBook.fetch(orderBook);
+
+    await expectError(fillOrder(nextOrderId.addn(100), minPositionSize.muln(2)), "OrderNotFound");
+  });
+});
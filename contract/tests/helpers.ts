@@ -0,0 +1,232 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+
+// Pyth price updates loaded from tests/fixtures by Anchor.toml, for the configured
+// BTC feed with Full verification. They are published far in the future so they
+// never go stale, which also makes any position or epoch they settle count as expired
+export const priceInBand = new PublicKey("Gnqa9NGWqY1T8fk3GezXiUwp5Rf2GpWHmoh8mbmopxYy");
+export const priceOutOfBand = new PublicKey("sC1UMGV3A9nzgiBWrXkYiA8sDdwDAa5N6UNU5CCJp6g");
+export const inBandPrice = 65000;
+export const outOfBandPrice = 75000;
+
+export function pda(program: Program<Vault>, ...seeds: (Buffer | Uint8Array)[]): PublicKey {
+  return PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+}
+
+export function u64Seed(value: number | anchor.BN): Buffer {
+  return new anchor.BN(value).toArrayLike(Buffer, "le", 8);
+}
+
+// Protocol accounts set up by pool.ts, which runs first
+export function protocolAccounts(program: Program<Vault>) {
+  const protocolConfig = pda(program, Buffer.from("protocol_config"));
+  const tradingPool = pda(program, Buffer.from("trading_pool"));
+
+  return {
+    protocolConfig,
+    roles: pda(program, Buffer.from("roles"), protocolConfig.toBuffer()),
+    treasury: pda(program, Buffer.from("treasury"), protocolConfig.toBuffer()),
+    tradingPool,
+    tradingPoolVault: pda(program, Buffer.from("trading_pool_vault"), tradingPool.toBuffer()),
+  };
+}
+
+// The provider wallet is the protocol admin, and acts as keeper in these tests
+export async function ensureKeeper(program: Program<Vault>, keeper: PublicKey) {
+  const { protocolConfig, roles } = protocolAccounts(program);
+  const current = await program.account.roles.fetch(roles);
+  if (current.keepers.some((member) => member.equals(keeper))) {
+    return;
+  }
+
+  await program.methods
+    .grantRole({ keeper: {} }, keeper)
+    .accounts({ authority: program.provider.publicKey, protocolConfig, roles })
+    .rpc();
+}
+
+// Exposure book and pool liquidity for positions against the pool, set up once
+export async function ensurePositionMarket(program: Program<Vault>) {
+  const provider = program.provider as anchor.AnchorProvider;
+  const { protocolConfig, tradingPool, tradingPoolVault } = protocolAccounts(program);
+  const exposureBook = pda(program, Buffer.from("exposure_book"), tradingPool.toBuffer());
+
+  if (!(await provider.connection.getAccountInfo(exposureBook))) {
+    await program.methods
+      .initExposureBook(new anchor.BN(50000), new anchor.BN(1000), new anchor.BN(100 * LAMPORTS_PER_SOL))
+      .accounts({ authority: provider.publicKey, tradingPool, exposureBook })
+      .rpc();
+  }
+
+  // Positions are capped at a share of free liquidity, so keep the pool deep
+  const pool = await program.account.tradingPool.fetch(tradingPool);
+  if (pool.totalPoolAmount.sub(pool.worstCaseLiability).lt(new anchor.BN(50 * LAMPORTS_PER_SOL))) {
+    await program.methods
+      .fundPool(new anchor.BN(100 * LAMPORTS_PER_SOL))
+      .accounts({ authority: provider.publicKey, protocolConfig, tradingPool, tradingPoolVault })
+      .rpc();
+  }
+
+  return exposureBook;
+}
+
+// Order book for paired positions, order fills and quotes, set up once
+export async function ensureOrderBook(program: Program<Vault>) {
+  const { tradingPool } = protocolAccounts(program);
+  const orderBook = pda(program, Buffer.from("order_book"), tradingPool.toBuffer());
+  const orderBookVault = pda(program, Buffer.from("order_book_vault"), orderBook.toBuffer());
+
+  if (!(await program.provider.connection.getAccountInfo(orderBook))) {
+    await program.methods
+      .initOrderBook()
+      .accounts({ authority: program.provider.publicKey, tradingPool, orderBook, orderBookVault })
+      .rpc();
+  }
+
+  return { orderBook, orderBookVault };
+}
+
+export function positionAddress(program: Program<Vault>, user: PublicKey, positionId: number) {
+  return pda(program, Buffer.from("position"), user.toBuffer(), u64Seed(positionId));
+}
+
+export function indexPageAddress(program: Program<Vault>, user: PublicKey, positionId: number) {
+  // Index pages hold 32 position ids each
+  return pda(program, Buffer.from("position_index"), user.toBuffer(), u64Seed(Math.floor(positionId / 32)));
+}
+
+// A new user with an initialized vault holding `deposit` lamports
+export async function fundedUser(program: Program<Vault>, deposit: number) {
+  const provider = program.provider as anchor.AnchorProvider;
+  const user = Keypair.generate();
+  const vaultState = pda(program, Buffer.from("vault_state"), user.publicKey.toBuffer());
+  const vault = pda(program, Buffer.from("vault"), vaultState.toBuffer());
+
+  const airdropTx = await provider.connection.requestAirdrop(
+    user.publicKey,
+    deposit + 2 * LAMPORTS_PER_SOL
+  );
+  await provider.connection.confirmTransaction(airdropTx);
+
+  await program.methods
+    .initialize()
+    .accounts({ user: user.publicKey, vaultState, vault })
+    .signers([user])
+    .rpc();
+  await program.methods
+    .deposit(new anchor.BN(deposit), new anchor.BN(0))
+    .accounts({ user: user.publicKey, vault, vaultState })
+    .signers([user])
+    .rpc();
+
+  return { user, vaultState, vault };
+}
+
+export async function expectError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+  } catch (error) {
+    expect(error.error?.errorCode?.code, error.toString()).to.equal(code);
+    return;
+  }
+  expect.fail(`Expected the transaction to fail with ${code}`);
+}
+
+// Opens a pool position at the user's next position id and returns its address and id.
+// A referrer is the address of a registered Referrer account
+export async function openPosition(
+  program: Program<Vault>,
+  owner: Awaited<ReturnType<typeof fundedUser>>,
+  positionType: { stayIn: {} } | { breakout: {} },
+  amount: number,
+  lowerBound = 60000,
+  upperBound = 70000,
+  referrer: PublicKey | null = null
+) {
+  const { protocolConfig, treasury, tradingPool, tradingPoolVault } = protocolAccounts(program);
+  const user = owner.user.publicKey;
+  const positionId = (await program.account.vaultState.fetch(owner.vaultState)).nextPositionId.toNumber();
+  const position = positionAddress(program, user, positionId);
+
+  await program.methods
+    .createPosition(positionType, new anchor.BN(lowerBound), new anchor.BN(upperBound), new anchor.BN(amount), false)
+    .accounts({
+      user,
+      position,
+      userVault: owner.vault,
+      userVaultState: owner.vaultState,
+      tradingPool,
+      tradingPoolVault,
+      protocolConfig,
+      treasury,
+      userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+      positionIndex: indexPageAddress(program, user, positionId),
+      referrer,
+      exposureBook: pda(program, Buffer.from("exposure_book"), tradingPool.toBuffer()),
+      priceUpdate: priceInBand,
+      positionMint: null,
+      userPositionToken: null,
+      tokenProgram: null,
+      associatedTokenProgram: null,
+    })
+    .signers([owner.user])
+    .rpc();
+
+  return { position, positionId };
+}
+
+// Settles a pool position as the keeper, against the given price update
+export async function checkPosition(
+  program: Program<Vault>,
+  user: PublicKey,
+  position: PublicKey,
+  priceUpdate: PublicKey
+) {
+  const { protocolConfig, roles, tradingPool } = protocolAccounts(program);
+
+  await program.methods
+    .checkPosition()
+    .accounts({
+      keeper: program.provider.publicKey,
+      user,
+      position,
+      tradingPool,
+      exposureBook: pda(program, Buffer.from("exposure_book"), tradingPool.toBuffer()),
+      protocolConfig,
+      roles,
+      priceUpdate,
+      userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+    })
+    .rpc();
+}
+
+// Claims a settled pool position into its owner's vault
+export async function claimPosition(
+  program: Program<Vault>,
+  owner: Awaited<ReturnType<typeof fundedUser>>,
+  position: PublicKey
+) {
+  const { protocolConfig, treasury, tradingPool, tradingPoolVault } = protocolAccounts(program);
+  const user = owner.user.publicKey;
+  const { positionId } = await program.account.positionState.fetch(position);
+
+  await program.methods
+    .claimPosition()
+    .accounts({
+      user,
+      position,
+      userVault: owner.vault,
+      userVaultState: owner.vaultState,
+      tradingPool,
+      tradingPoolVault,
+      protocolConfig,
+      treasury,
+      userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+      positionIndex: indexPageAddress(program, user, positionId.toNumber()),
+    })
+    .signers([owner.user])
+    .rpc();
+}
@@ -0,0 +1,151 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, SystemProgram, Transaction, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  ensureKeeper,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  indexPageAddress,
+  pda,
+  positionAddress,
+  priceInBand,
+  priceOutOfBand,
+  protocolAccounts,
+} from "./helpers";
+
+describe("position ladders", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const keeper = provider.wallet.publicKey;
+  const { protocolConfig, roles, treasury, tradingPool, tradingPoolVault } = protocolAccounts(program);
+  const amount = new anchor.BN(LAMPORTS_PER_SOL / 2);
+
+  const rungs = [
+    { positionType: { breakout: {} }, lowerBound: new anchor.BN(60000), upperBound: new anchor.BN(70000), amount },
+    { positionType: { stayIn: {} }, lowerBound: new anchor.BN(62000), upperBound: new anchor.BN(68000), amount },
+  ];
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+  let exposureBook: PublicKey;
+
+  function writable(positions: PublicKey[]) {
+    return positions.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }));
+  }
+
+  function createLadder(ladderRungs: typeof rungs, positions: PublicKey[]) {
+    const user = owner.user.publicKey;
+
+    return program.methods
+      .createPositionLadder(ladderRungs)
+      .accounts({
+        user,
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        tradingPool,
+        tradingPoolVault,
+        protocolConfig,
+        treasury,
+        userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+        positionIndex: indexPageAddress(program, user, 0),
+        nextPositionIndex: null,
+        exposureBook,
+        priceUpdate: priceInBand,
+      })
+      .remainingAccounts(writable(positions))
+      .signers([owner.user]);
+  }
+
+  function checkLadder(ladderId: number, positions: PublicKey[]) {
+    const user = owner.user.publicKey;
+
+    return program.methods
+      .checkPositionLadder(user, new anchor.BN(ladderId))
+      .accounts({
+        keeper,
+        tradingPool,
+        exposureBook,
+        protocolConfig,
+        roles,
+        priceUpdate: priceOutOfBand,
+        userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+      })
+      .remainingAccounts(writable(positions));
+  }
+
+  before(async () => {
+    await ensureKeeper(program, keeper);
+    exposureBook = await ensurePositionMarket(program);
+    owner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+  });
+
+  it("Rejects rung accounts that are not the next position ids", async () => {
+    const user = owner.user.publicKey;
+
+    await expectError(
+      createLadder(rungs, [positionAddress(program, user, 1), positionAddress(program, user, 0)]).rpc(),
+      "InvalidLadder"
+    );
+  });
+
+  it("Opens a ladder, including a rung whose address was funded beforehand", async () => {
+    const user = owner.user.publicKey;
+    const positions = [positionAddress(program, user, 0), positionAddress(program, user, 1)];
+
+    // Anyone can send lamports to a position address before it is created
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({ fromPubkey: keeper, toPubkey: positions[1], lamports: 1_000_000 })
+      )
+    );
+
+    await createLadder(rungs, positions).rpc();
+
+    for (const [index, position] of positions.entries()) {
+      const rung = await program.account.positionState.fetch(position);
+      expect(rung.positionId.toNumber()).to.equal(index);
+      expect(rung.ladderRung).to.equal(index + 1);
+      expect(rung.status).to.equal(0);
+
+      const info = await provider.connection.getAccountInfo(position);
+      expect(info.owner.toBase58()).to.equal(program.programId.toBase58());
+      expect(info.data.length).to.equal(program.account.positionState.size);
+    }
+  });
+
+  it("Rejects settling positions from another ladder together", async () => {
+    const user = owner.user.publicKey;
+    const otherLadder = positionAddress(program, user, 2);
+    await createLadder(rungs.slice(0, 1), [otherLadder]).rpc();
+
+    await expectError(
+      checkLadder(0, [positionAddress(program, user, 0), otherLadder]).rpc(),
+      "InvalidLadder"
+    );
+  });
+
+  it("Settles every rung of a ladder against one price", async () => {
+    const user = owner.user.publicKey;
+    const positions = [positionAddress(program, user, 0), positionAddress(program, user, 1)];
+
+    await checkLadder(0, positions).rpc();
+
+    // Both bands are broken by the same price: the Breakout rung wins, the StayIn rung loses
+    const [breakout, stayIn] = await Promise.all(
+      positions.map((position) => program.account.positionState.fetch(position))
+    );
+    expect(breakout.status).to.equal(1);
+    expect(breakout.payoutPercentage).to.be.greaterThan(100);
+    expect(stayIn.status).to.equal(1);
+    expect(stayIn.payoutPercentage).to.be.lessThan(100);
+    expect(breakout.settlementPrice.toNumber()).to.equal(stayIn.settlementPrice.toNumber());
+
+    const stats = await program.account.userStats.fetch(pda(program, Buffer.from("user_stats"), user.toBuffer()));
+    expect(stats.breakoutWins.toNumber()).to.equal(1);
+    expect(stats.stayInLosses.toNumber()).to.equal(1);
+  });
+});
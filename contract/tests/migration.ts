@@ -0,0 +1,162 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+
+// Accounts loaded from tests/fixtures by Anchor.toml, written in the layouts
+// deployed before accounts carried a version byte. The vault state and position
+// sit at the fixture user's PDAs, the position under order id 2
+const legacyVaultState = new PublicKey("GiNCFJum73yzm3v5JCYYhkLKE5SKMbVNHc5kVWmS23Aj");
+const legacyPosition = new PublicKey("7WYQeSKZ4CVZdK3nHPESNPQnGn6XKomx4GrQERSKeFaL");
+const legacyTradingPool = new PublicKey("8cAbGPZ1RgbiFPmH3RCidh8zHEZhRDe5Pw8cVEw9EJgf");
+
+const fixtureUser = new PublicKey("74aE4bTrjcAMXfgXmxpftdHbL6zeFftUsF1u9cNWPSCo");
+const fixturePoolAuthority = new PublicKey("AcuqqEZ1xXB5BD4xg8GUBB7bRVu7X6GcNqV83EThPuiV");
+
+describe("account migrations", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const payer = provider.wallet.publicKey;
+
+  // Set up by pool.ts, which runs first
+  const [tradingPool] = PublicKey.findProgramAddressSync(
+    [Buffer.from("trading_pool")],
+    program.programId
+  );
+  // Page 0 of the fixture user's index, which lists the legacy position once migrated
+  const [positionIndex] = PublicKey.findProgramAddressSync(
+    [Buffer.from("position_index"), fixtureUser.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+    program.programId
+  );
+
+  it("Legacy accounts cannot be read with the current layout", async () => {
+    try {
+      await program.account.vaultState.fetch(legacyVaultState);
+      expect.fail("Legacy vault state should not deserialize");
+    } catch (error) {
+      expect(error.message).to.not.include("Legacy vault state should not deserialize");
+    }
+  });
+
+  it("Migrates a legacy vault state in place", async () => {
+    await program.methods
+      .migrateVaultState()
+      .accounts({ payer, vaultState: legacyVaultState })
+      .rpc();
+
+    // Counters start empty until the user's positions are migrated
+    const vaultState = await program.account.vaultState.fetch(legacyVaultState);
+    expect(vaultState.version).to.equal(1);
+    expect(vaultState.authority.toBase58()).to.equal(fixtureUser.toBase58());
+    expect(vaultState.nextPositionId.toNumber()).to.equal(0);
+    expect(vaultState.openPositions).to.equal(0);
+    expect(vaultState.openNotional.toNumber()).to.equal(0);
+    expect(vaultState.openMaxPayout.toNumber()).to.equal(0);
+    expect(vaultState.vaultBump).to.equal(253);
+    expect(vaultState.stateBump).to.equal(255);
+
+    const info = await provider.connection.getAccountInfo(legacyVaultState);
+    expect(info.data.length).to.equal(program.account.vaultState.size);
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(info.data.length);
+    expect(info.lamports).to.be.at.least(rent);
+  });
+
+  it("Migrates a legacy settled position to the zero-copy layout", async () => {
+    const poolBefore = await program.account.tradingPool.fetch(tradingPool);
+
+    await program.methods
+      .migratePosition(new anchor.BN(2))
+      .accounts({ payer, position: legacyPosition, userVaultState: legacyVaultState, tradingPool, positionIndex })
+      .rpc();
+
+    // Enums are stored as their discriminant: Breakout = 1, Settled = 1
+    const position = await program.account.positionState.fetch(legacyPosition);
+    expect(position.version).to.equal(2);
+    expect(position.user.toBase58()).to.equal(fixtureUser.toBase58());
+    expect(position.positionType).to.equal(1);
+    expect(position.positionId.toNumber()).to.equal(2);
+    expect(position.duration.toNumber()).to.equal(86400);
+    expect(position.status).to.equal(1);
+    expect(position.amount.toNumber()).to.equal(LAMPORTS_PER_SOL);
+    expect(position.isSettled).to.equal(1);
+    expect(position.payoutPercentage).to.equal(180);
+    expect(position.positionMint.toBase58()).to.equal(PublicKey.default.toBase58());
+    expect(position.hasRollConfig).to.equal(0);
+    expect(position.ladderRung).to.equal(0);
+    expect(position.bump).to.equal(254);
+
+    const info = await provider.connection.getAccountInfo(legacyPosition);
+    expect(info.data.length).to.equal(program.account.positionState.size);
+
+    // The next id moves past the order id, and the unclaimed position counts as open
+    const vaultState = await program.account.vaultState.fetch(legacyVaultState);
+    expect(vaultState.nextPositionId.toNumber()).to.equal(3);
+    expect(vaultState.openPositions).to.equal(1);
+    expect(vaultState.openNotional.toNumber()).to.equal(LAMPORTS_PER_SOL);
+    expect(vaultState.openMaxPayout.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+
+    // A settled position only owes its known payout
+    const payout = 1.8 * LAMPORTS_PER_SOL;
+    const poolAfter = await program.account.tradingPool.fetch(tradingPool);
+    expect(poolAfter.worstCaseLiability.sub(poolBefore.worstCaseLiability).toNumber()).to.equal(payout);
+    expect(poolAfter.expectedLiability.sub(poolBefore.expectedLiability).toNumber()).to.equal(payout);
+
+    // Listed on its owner's index page, which claiming it requires
+    const page = await program.account.positionIndex.fetch(positionIndex);
+    expect(page.user.toBase58()).to.equal(fixtureUser.toBase58());
+    expect(page.positionIds.map((id) => id.toNumber())).to.deep.equal([2]);
+  });
+
+  it("Migrates a legacy trading pool in place", async () => {
+    await program.methods
+      .migrateTradingPool()
+      .accounts({ payer, tradingPool: legacyTradingPool })
+      .rpc();
+
+    // Fields added since take their defaults, liabilities start at zero
+    const pool = await program.account.tradingPool.fetch(legacyTradingPool);
+    expect(pool.version).to.equal(1);
+    expect(pool.authority.toBase58()).to.equal(fixturePoolAuthority.toBase58());
+    expect(pool.pendingAuthority).to.be.null;
+    expect(pool.totalActiveAmount.toNumber()).to.equal(10 * LAMPORTS_PER_SOL);
+    expect(pool.totalPoolAmount.toNumber()).to.equal(100 * LAMPORTS_PER_SOL);
+    expect(pool.worstCaseLiability.toNumber()).to.equal(0);
+    expect(pool.expectedLiability.toNumber()).to.equal(0);
+    expect(pool.maxUtilizationBps).to.equal(8000);
+    expect(pool.maxUserOpenPositions).to.equal(0);
+    expect(pool.minPositionSize.toNumber()).to.equal(LAMPORTS_PER_SOL / 10);
+    expect(pool.lpMintBump).to.equal(0);
+    expect(pool.bump).to.equal(255);
+    expect(pool.vaultBump).to.equal(254);
+
+    const info = await provider.connection.getAccountInfo(legacyTradingPool);
+    expect(info.data.length).to.equal(program.account.tradingPool.size);
+  });
+
+  it("Rejects migrating an account twice", async () => {
+    try {
+      await program.methods
+        .migrateVaultState()
+        .accounts({ payer, vaultState: legacyVaultState })
+        .rpc();
+      expect.fail("Second migration should be rejected");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("AccountAlreadyMigrated");
+    }
+  });
+
+  it("Rejects an account of another type", async () => {
+    try {
+      await program.methods
+        .migratePosition(new anchor.BN(2))
+        .accounts({ payer, position: legacyVaultState, userVaultState: legacyVaultState, tradingPool, positionIndex: null })
+        .rpc();
+      expect.fail("Vault state should not migrate as a position");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("InvalidAccountLayout");
+    }
+  });
+});
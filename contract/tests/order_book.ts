@@ -0,0 +1,290 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { Keypair, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  ensureKeeper,
+  ensureOrderBook,
+  expectError,
+  fundedUser,
+  pda,
+  priceInBand,
+  priceOutOfBand,
+  protocolAccounts,
+  u64Seed,
+} from "./helpers";
+
+describe("order book", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const admin = provider.wallet.publicKey;
+  const { protocolConfig, roles, treasury } = protocolAccounts(program);
+
+  // Pays the paired escrow rent when matching, so its refund can be measured exactly
+  const keeper = Keypair.generate();
+
+  // Set by pool.ts: 5% winnings fee
+  const winningsFeeBps = 500;
+
+  let orderBook: PublicKey;
+  let orderBookVault: PublicKey;
+  let stayInUser: Awaited<ReturnType<typeof fundedUser>>;
+  let breakoutUser: Awaited<ReturnType<typeof fundedUser>>;
+  let rent: number;
+
+  const stayInSize = new anchor.BN(LAMPORTS_PER_SOL);
+  const breakoutSize = new anchor.BN(0.6 * LAMPORTS_PER_SOL);
+  let stayInOrder: PublicKey;
+  let breakoutOrder: PublicKey;
+  let pairedPosition: PublicKey;
+  let pairedEscrow: PublicKey;
+
+  async function placeOrder(
+    owner: Awaited<ReturnType<typeof fundedUser>>,
+    positionType: { stayIn: {} } | { breakout: {} },
+    size: anchor.BN,
+    lowerBound = 60000,
+    upperBound = 70000
+  ) {
+    const { nextOrderId } = await program.account.orderBook.fetch(orderBook);
+    const order = pda(program, Buffer.from("order"), orderBook.toBuffer(), u64Seed(nextOrderId));
+    const expiry = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+
+    await program.methods
+      .placeOrder(positionType, new anchor.BN(lowerBound), new anchor.BN(upperBound), size, expiry)
+      .accounts({
+        user: owner.user.publicKey,
+        orderBook,
+        orderBookVault,
+        order,
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        protocolConfig,
+      })
+      .signers([owner.user])
+      .rpc();
+
+    return order;
+  }
+
+  function matchOrders(stayIn: PublicKey, breakout: PublicKey, paired: PublicKey) {
+    return program.methods
+      .matchOrders()
+      .accounts({
+        keeper: keeper.publicKey,
+        orderBook,
+        orderBookVault,
+        stayInOrder: stayIn,
+        breakoutOrder: breakout,
+        pairedPosition: paired,
+        pairedEscrow: pda(program, Buffer.from("paired_escrow"), paired.toBuffer()),
+        protocolConfig,
+        roles,
+      })
+      .signers([keeper])
+      .rpc();
+  }
+
+  async function nextPairedPosition() {
+    const { nextMatchId } = await program.account.orderBook.fetch(orderBook);
+    return pda(program, Buffer.from("paired_position"), orderBook.toBuffer(), u64Seed(nextMatchId));
+  }
+
+  function settlePairedPosition(priceUpdate: PublicKey) {
+    return program.methods
+      .settlePairedPosition()
+      .accounts({ keeper: admin, orderBook, pairedPosition, protocolConfig, roles, priceUpdate })
+      .rpc();
+  }
+
+  function claimPairedPosition(owner: Awaited<ReturnType<typeof fundedUser>>, rentPayer: PublicKey) {
+    return program.methods
+      .claimPairedPosition()
+      .accounts({
+        user: owner.user.publicKey,
+        orderBook,
+        pairedPosition,
+        pairedEscrow,
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        protocolConfig,
+        treasury,
+        rentPayer,
+      })
+      .signers([owner.user])
+      .rpc();
+  }
+
+  before(async () => {
+    ({ orderBook, orderBookVault } = await ensureOrderBook(program));
+    await ensureKeeper(program, admin);
+    await ensureKeeper(program, keeper.publicKey);
+
+    const airdropTx = await provider.connection.requestAirdrop(keeper.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(airdropTx);
+
+    stayInUser = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    breakoutUser = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+  });
+
+  it("Locks an order's size in the order book vault", async () => {
+    const userVaultBefore = await provider.connection.getBalance(stayInUser.vault);
+    const bookVaultBefore = await provider.connection.getBalance(orderBookVault);
+    const bookBefore = await program.account.orderBook.fetch(orderBook);
+
+    stayInOrder = await placeOrder(stayInUser, { stayIn: {} }, stayInSize);
+
+    const order = await program.account.order.fetch(stayInOrder);
+    expect(order.orderId.toNumber()).to.equal(bookBefore.nextOrderId.toNumber());
+    expect(order.owner.toString()).to.equal(stayInUser.user.publicKey.toString());
+    expect(order.size.toString()).to.equal(stayInSize.toString());
+    expect(order.filledAmount.toNumber()).to.equal(0);
+    expect(order.status).to.deep.equal({ open: {} });
+
+    const bookAfter = await program.account.orderBook.fetch(orderBook);
+    expect(bookAfter.openOrders.toNumber()).to.equal(bookBefore.openOrders.toNumber() + 1);
+    expect(await provider.connection.getBalance(stayInUser.vault)).to.equal(
+      userVaultBefore - stayInSize.toNumber()
+    );
+    expect(await provider.connection.getBalance(orderBookVault)).to.equal(
+      bookVaultBefore + stayInSize.toNumber()
+    );
+  });
+
+  it("Cancels an order and returns its size to the user vault", async () => {
+    const order = await placeOrder(breakoutUser, { breakout: {} }, breakoutSize);
+    const userVaultBefore = await provider.connection.getBalance(breakoutUser.vault);
+    const bookBefore = await program.account.orderBook.fetch(orderBook);
+
+    await program.methods
+      .cancelOrder()
+      .accounts({
+        user: breakoutUser.user.publicKey,
+        orderBook,
+        orderBookVault,
+        order,
+        userVault: breakoutUser.vault,
+        userVaultState: breakoutUser.vaultState,
+      })
+      .signers([breakoutUser.user])
+      .rpc();
+
+    expect(await provider.connection.getAccountInfo(order)).to.be.null;
+    expect(await provider.connection.getBalance(breakoutUser.vault)).to.equal(
+      userVaultBefore + breakoutSize.toNumber()
+    );
+    const bookAfter = await program.account.orderBook.fetch(orderBook);
+    expect(bookAfter.openOrders.toNumber()).to.equal(bookBefore.openOrders.toNumber() - 1);
+  });
+
+  it("Rejects matching orders on different bands or from the same owner", async () => {
+    const otherBand = await placeOrder(breakoutUser, { breakout: {} }, breakoutSize, 61000, 70000);
+    const sameOwner = await placeOrder(stayInUser, { breakout: {} }, breakoutSize);
+    const paired = await nextPairedPosition();
+
+    await expectError(matchOrders(stayInOrder, otherBand, paired), "OrdersNotMatchable");
+    await expectError(matchOrders(stayInOrder, sameOwner, paired), "OrdersNotMatchable");
+    await expectError(matchOrders(otherBand, stayInOrder, paired), "OrdersNotMatchable");
+  });
+
+  it("Matches the smaller remaining size into a paired escrow", async () => {
+    breakoutOrder = await placeOrder(breakoutUser, { breakout: {} }, breakoutSize);
+    pairedPosition = await nextPairedPosition();
+    pairedEscrow = pda(program, Buffer.from("paired_escrow"), pairedPosition.toBuffer());
+
+    const bookVaultBefore = await provider.connection.getBalance(orderBookVault);
+    const bookBefore = await program.account.orderBook.fetch(orderBook);
+
+    await matchOrders(stayInOrder, breakoutOrder, pairedPosition);
+
+    // The breakout order is used up, the StayIn order keeps resting with the rest
+    const stayIn = await program.account.order.fetch(stayInOrder);
+    expect(stayIn.filledAmount.toString()).to.equal(breakoutSize.toString());
+    expect(stayIn.status).to.deep.equal({ open: {} });
+    const breakout = await program.account.order.fetch(breakoutOrder);
+    expect(breakout.filledAmount.toString()).to.equal(breakoutSize.toString());
+    expect(breakout.status).to.deep.equal({ filled: {} });
+
+    const bookAfter = await program.account.orderBook.fetch(orderBook);
+    expect(bookAfter.openOrders.toNumber()).to.equal(bookBefore.openOrders.toNumber() - 1);
+    expect(bookAfter.nextMatchId.toNumber()).to.equal(bookBefore.nextMatchId.toNumber() + 1);
+
+    const paired = await program.account.pairedPosition.fetch(pairedPosition);
+    expect(paired.stayInUser.toString()).to.equal(stayInUser.user.publicKey.toString());
+    expect(paired.breakoutUser.toString()).to.equal(breakoutUser.user.publicKey.toString());
+    expect(paired.stayInStake.toString()).to.equal(breakoutSize.toString());
+    expect(paired.breakoutStake.toString()).to.equal(breakoutSize.toString());
+    expect(paired.rentPayer.toString()).to.equal(keeper.publicKey.toString());
+    expect(paired.status).to.deep.equal({ active: {} });
+
+    // Both fills move out of the order book vault, the keeper tops up the rent
+    expect(await provider.connection.getBalance(orderBookVault)).to.equal(
+      bookVaultBefore - 2 * breakoutSize.toNumber()
+    );
+    expect(await provider.connection.getBalance(pairedEscrow)).to.equal(rent + 2 * breakoutSize.toNumber());
+  });
+
+  it("Leaves a paired position active while the price is in band", async () => {
+    await settlePairedPosition(priceInBand);
+
+    const paired = await program.account.pairedPosition.fetch(pairedPosition);
+    expect(paired.status).to.deep.equal({ active: {} });
+    expect(paired.settlementData).to.be.null;
+  });
+
+  it("Splits the escrow between both sides on a breakout", async () => {
+    await settlePairedPosition(priceOutOfBand);
+
+    const paired = await program.account.pairedPosition.fetch(pairedPosition);
+    expect(paired.status).to.deep.equal({ settled: {} });
+
+    // StayIn keeps its time-weighted refund, Breakout takes the rest of both stakes
+    expect(paired.stayInPayout.lt(breakoutSize)).to.be.true;
+    expect(paired.stayInPayout.add(paired.breakoutPayout).toString()).to.equal(
+      breakoutSize.muln(2).toString()
+    );
+  });
+
+  it("Pays the winner net of the winnings fee", async () => {
+    const paired = await program.account.pairedPosition.fetch(pairedPosition);
+    const winningsFee = paired.breakoutPayout.sub(paired.breakoutStake).muln(winningsFeeBps).divn(10000);
+
+    const vaultBefore = await provider.connection.getBalance(breakoutUser.vault);
+    const treasuryBefore = await provider.connection.getBalance(treasury);
+
+    await claimPairedPosition(breakoutUser, keeper.publicKey);
+
+    expect(await provider.connection.getBalance(breakoutUser.vault)).to.equal(
+      vaultBefore + paired.breakoutPayout.sub(winningsFee).toNumber()
+    );
+    expect(await provider.connection.getBalance(treasury)).to.equal(treasuryBefore + winningsFee.toNumber());
+    expect(await provider.connection.getBalance(pairedEscrow)).to.equal(rent + paired.stayInPayout.toNumber());
+
+    await expectError(claimPairedPosition(breakoutUser, keeper.publicKey), "AlreadyClaimed");
+  });
+
+  it("Refunds the escrow rent to the keeper on the last claim", async () => {
+    const paired = await program.account.pairedPosition.fetch(pairedPosition);
+
+    await expectError(claimPairedPosition(stayInUser, admin), "UnauthorizedAccess");
+
+    const vaultBefore = await provider.connection.getBalance(stayInUser.vault);
+    const keeperBefore = await provider.connection.getBalance(keeper.publicKey);
+
+    await claimPairedPosition(stayInUser, keeper.publicKey);
+
+    // The losing side's refund is below its stake, so it pays no winnings fee
+    expect(await provider.connection.getBalance(stayInUser.vault)).to.equal(
+      vaultBefore + paired.stayInPayout.toNumber()
+    );
+    expect(await provider.connection.getBalance(keeper.publicKey)).to.equal(keeperBefore + rent);
+    expect(await provider.connection.getBalance(pairedEscrow)).to.equal(0);
+
+    const claimed = await program.account.pairedPosition.fetch(pairedPosition);
+    expect(claimed.status).to.deep.equal({ claimed: {} });
+  });
+});
@@ -0,0 +1,139 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  ensureKeeper,
+  ensureOrderBook,
+  expectError,
+  fundedUser,
+  pda,
+  priceOutOfBand,
+  protocolAccounts,
+  u64Seed,
+} from "./helpers";
+
+describe("paired positions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const admin = provider.wallet.publicKey;
+  const { protocolConfig, roles, treasury } = protocolAccounts(program);
+  const stake = new anchor.BN(LAMPORTS_PER_SOL);
+
+  let orderBook: PublicKey;
+  let stayInUser: Awaited<ReturnType<typeof fundedUser>>;
+  let breakoutUser: Awaited<ReturnType<typeof fundedUser>>;
+  let pairedPosition: PublicKey;
+  let pairedEscrow: PublicKey;
+  let rent: number;
+
+  function createPairedPosition(
+    stayIn: Awaited<ReturnType<typeof fundedUser>>,
+    breakout: Awaited<ReturnType<typeof fundedUser>>,
+    paired: PublicKey
+  ) {
+    const signers = stayIn === breakout ? [stayIn.user] : [stayIn.user, breakout.user];
+
+    return program.methods
+      .createPairedPosition(new anchor.BN(60000), new anchor.BN(70000), stake)
+      .accounts({
+        stayInUser: stayIn.user.publicKey,
+        breakoutUser: breakout.user.publicKey,
+        orderBook,
+        pairedPosition: paired,
+        pairedEscrow: pda(program, Buffer.from("paired_escrow"), paired.toBuffer()),
+        stayInVault: stayIn.vault,
+        stayInVaultState: stayIn.vaultState,
+        breakoutVault: breakout.vault,
+        breakoutVaultState: breakout.vaultState,
+        protocolConfig,
+      })
+      .signers(signers)
+      .rpc();
+  }
+
+  function claimPairedPosition(owner: Awaited<ReturnType<typeof fundedUser>>, rentPayer: PublicKey) {
+    return program.methods
+      .claimPairedPosition()
+      .accounts({
+        user: owner.user.publicKey,
+        orderBook,
+        pairedPosition,
+        pairedEscrow,
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        protocolConfig,
+        treasury,
+        rentPayer,
+      })
+      .signers([owner.user])
+      .rpc();
+  }
+
+  before(async () => {
+    ({ orderBook } = await ensureOrderBook(program));
+    await ensureKeeper(program, admin);
+
+    stayInUser = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    breakoutUser = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+    const { nextMatchId } = await program.account.orderBook.fetch(orderBook);
+    pairedPosition = pda(program, Buffer.from("paired_position"), orderBook.toBuffer(), u64Seed(nextMatchId));
+    pairedEscrow = pda(program, Buffer.from("paired_escrow"), pairedPosition.toBuffer());
+  });
+
+  it("Rejects a paired position between a user and itself", async () => {
+    await expectError(createPairedPosition(stayInUser, stayInUser, pairedPosition), "OrdersNotMatchable");
+  });
+
+  it("Escrows both stakes with the StayIn user paying the rent", async () => {
+    const stayInVaultBefore = await provider.connection.getBalance(stayInUser.vault);
+    const breakoutVaultBefore = await provider.connection.getBalance(breakoutUser.vault);
+
+    await createPairedPosition(stayInUser, breakoutUser, pairedPosition);
+
+    expect(await provider.connection.getBalance(stayInUser.vault)).to.equal(stayInVaultBefore - stake.toNumber());
+    expect(await provider.connection.getBalance(breakoutUser.vault)).to.equal(
+      breakoutVaultBefore - stake.toNumber()
+    );
+    expect(await provider.connection.getBalance(pairedEscrow)).to.equal(rent + 2 * stake.toNumber());
+
+    const paired = await program.account.pairedPosition.fetch(pairedPosition);
+    expect(paired.stayInStake.toString()).to.equal(stake.toString());
+    expect(paired.breakoutStake.toString()).to.equal(stake.toString());
+    expect(paired.rentPayer.toString()).to.equal(stayInUser.user.publicKey.toString());
+  });
+
+  it("Lets either side claim first and refunds the rent on the last claim", async () => {
+    await program.methods
+      .settlePairedPosition()
+      .accounts({ keeper: admin, orderBook, pairedPosition, protocolConfig, roles, priceUpdate: priceOutOfBand })
+      .rpc();
+
+    const paired = await program.account.pairedPosition.fetch(pairedPosition);
+    expect(paired.stayInPayout.add(paired.breakoutPayout).toString()).to.equal(stake.muln(2).toString());
+
+    // The losing side claims first and leaves the winner's payout and the rent behind
+    await claimPairedPosition(stayInUser, stayInUser.user.publicKey);
+    expect(await provider.connection.getBalance(pairedEscrow)).to.equal(rent + paired.breakoutPayout.toNumber());
+
+    const rentPayerBefore = await provider.connection.getBalance(stayInUser.user.publicKey);
+    await claimPairedPosition(breakoutUser, stayInUser.user.publicKey);
+
+    expect(await provider.connection.getBalance(stayInUser.user.publicKey)).to.equal(rentPayerBefore + rent);
+    expect(await provider.connection.getBalance(pairedEscrow)).to.equal(0);
+
+    const claimed = await program.account.pairedPosition.fetch(pairedPosition);
+    expect(claimed.status).to.deep.equal({ claimed: {} });
+  });
+
+  it("Rejects a claim from a user who is not on either side", async () => {
+    const stranger = await fundedUser(program, LAMPORTS_PER_SOL);
+
+    await expectError(claimPairedPosition(stranger, stayInUser.user.publicKey), "UnauthorizedAccess");
+  });
+});
@@ -0,0 +1,403 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import { createHash } from "crypto";
+
+describe("trading pool liquidity", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+
+  // The provider wallet deploys the program, so it is the upgrade authority
+  const admin = provider.wallet.publicKey;
+  const lp = anchor.web3.Keypair.generate();
+
+  // PDAs
+  let programData: PublicKey;
+  let protocolConfig: PublicKey;
+  let roles: PublicKey;
+  let treasury: PublicKey;
+  let tradingPool: PublicKey;
+  let tradingPoolVault: PublicKey;
+  let lpMint: PublicKey;
+  let adminLpToken: PublicKey;
+  let lpToken: PublicKey;
+  let poolLpToken: PublicKey;
+
+  const depositAmount = LAMPORTS_PER_SOL;
+  const minLockedShares = 1_000_000;
+
+  before(async () => {
+    [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    [protocolConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("protocol_config")],
+      program.programId
+    );
+    [roles] = PublicKey.findProgramAddressSync(
+      [Buffer.from("roles"), protocolConfig.toBuffer()],
+      program.programId
+    );
+    [treasury] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), protocolConfig.toBuffer()],
+      program.programId
+    );
+    [tradingPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("trading_pool")],
+      program.programId
+    );
+    [tradingPoolVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("trading_pool_vault"), tradingPool.toBuffer()],
+      program.programId
+    );
+    [lpMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), tradingPool.toBuffer()],
+      program.programId
+    );
+    adminLpToken = anchor.utils.token.associatedAddress({
+      mint: lpMint,
+      owner: admin,
+    });
+    lpToken = anchor.utils.token.associatedAddress({
+      mint: lpMint,
+      owner: lp.publicKey,
+    });
+    poolLpToken = anchor.utils.token.associatedAddress({
+      mint: lpMint,
+      owner: tradingPool,
+    });
+
+    const airdropTx = await provider.connection.requestAirdrop(
+      lp.publicKey,
+      10 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropTx);
+  });
+
+  it("Only the upgrade authority can initialize the protocol config", async () => {
+    try {
+      await program.methods
+        .initProtocolConfig(30, 500, 2000)
+        .accounts({
+          authority: lp.publicKey,
+          program: program.programId,
+          programData,
+          protocolConfig,
+          roles,
+          treasury,
+        })
+        .signers([lp])
+        .rpc();
+
+      expect.fail("Non upgrade authority should not initialize the protocol");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("UnauthorizedAccess");
+    }
+
+    await program.methods
+      .initProtocolConfig(30, 500, 2000)
+      .accounts({
+        authority: admin,
+        program: program.programId,
+        programData,
+        protocolConfig,
+        roles,
+        treasury,
+      })
+      .rpc();
+
+    const config = await program.account.protocolConfig.fetch(protocolConfig);
+    expect(config.authority.toBase58()).to.equal(admin.toBase58());
+  });
+
+  it("Only the admin can initialize the trading pool", async () => {
+    try {
+      await program.methods
+        .initTradingPool()
+        .accounts({
+          admin: lp.publicKey,
+          protocolConfig,
+          tradingPool,
+          tradingPoolVault,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([lp])
+        .rpc();
+
+      expect.fail("Non admin should not initialize the trading pool");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("UnauthorizedAccess");
+    }
+  });
+
+  it("Initializes the trading pool and LP mint", async () => {
+    await program.methods
+      .initTradingPool()
+      .accounts({
+        admin,
+        protocolConfig,
+        tradingPool,
+        tradingPoolVault,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .initLpMint()
+      .accounts({
+        admin,
+        tradingPool,
+        lpMint,
+        adminLpToken,
+      })
+      .rpc();
+
+    const pool = await program.account.tradingPool.fetch(tradingPool);
+    expect(pool.authority.toBase58()).to.equal(admin.toBase58());
+  });
+
+  it("Risk manager role gates the utilization cap", async () => {
+    try {
+      await program.methods
+        .updatePoolConfig(9000)
+        .accounts({
+          riskManager: lp.publicKey,
+          tradingPool,
+          protocolConfig,
+          roles,
+        })
+        .signers([lp])
+        .rpc();
+
+      expect.fail("Caller without the risk manager role should be rejected");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("UnauthorizedAccess");
+    }
+
+    await program.methods
+      .grantRole({ riskManager: {} }, admin)
+      .accounts({ authority: admin, protocolConfig, roles })
+      .rpc();
+
+    await program.methods
+      .updatePoolConfig(9000)
+      .accounts({ riskManager: admin, tradingPool, protocolConfig, roles })
+      .rpc();
+
+    const pool = await program.account.tradingPool.fetch(tradingPool);
+    expect(pool.maxUtilizationBps).to.equal(9000);
+  });
+
+  it("Risk manager sets per-user limits", async () => {
+    await program.methods
+      .updateUserLimits(10, new anchor.BN(50 * LAMPORTS_PER_SOL), new anchor.BN(150 * LAMPORTS_PER_SOL))
+      .accounts({ riskManager: admin, tradingPool, protocolConfig, roles })
+      .rpc();
+
+    const pool = await program.account.tradingPool.fetch(tradingPool);
+    expect(pool.maxUserOpenPositions).to.equal(10);
+    expect(pool.maxUserNotional.toNumber()).to.equal(50 * LAMPORTS_PER_SOL);
+    expect(pool.maxUserPayout.toNumber()).to.equal(150 * LAMPORTS_PER_SOL);
+  });
+
+  it("Risk manager sets position size limits", async () => {
+    try {
+      await program.methods
+        .updatePositionSizeLimits(new anchor.BN(LAMPORTS_PER_SOL / 100), new anchor.BN(0), 0)
+        .accounts({ riskManager: admin, tradingPool, protocolConfig, roles })
+        .rpc();
+      expect.fail("Minimum below the protocol floor should be rejected");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("InvalidPoolConfig");
+    }
+
+    await program.methods
+      .updatePositionSizeLimits(new anchor.BN(LAMPORTS_PER_SOL / 5), new anchor.BN(20 * LAMPORTS_PER_SOL), 1000)
+      .accounts({ riskManager: admin, tradingPool, protocolConfig, roles })
+      .rpc();
+
+    const pool = await program.account.tradingPool.fetch(tradingPool);
+    expect(pool.minPositionSize.toNumber()).to.equal(LAMPORTS_PER_SOL / 5);
+    expect(pool.maxPositionSize.toNumber()).to.equal(20 * LAMPORTS_PER_SOL);
+    expect(pool.maxPositionLiquidityBps).to.equal(1000);
+  });
+
+  it("Only the protocol authority funds the pool", async () => {
+    try {
+      await program.methods
+        .fundPool(new anchor.BN(LAMPORTS_PER_SOL))
+        .accounts({ authority: lp.publicKey, protocolConfig, tradingPool, tradingPoolVault })
+        .signers([lp])
+        .rpc();
+
+      expect.fail("Non authority should not fund the pool");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("UnauthorizedAccess");
+    }
+  });
+
+  it("Pool surplus withdrawal is capped at free liquidity before LPs join", async () => {
+    const surplus = 2 * LAMPORTS_PER_SOL;
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+    // The vault keeps its rent reserve out of the withdrawable surplus
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({ fromPubkey: admin, toPubkey: tradingPoolVault, lamports: rent })
+      )
+    );
+
+    await program.methods
+      .fundPool(new anchor.BN(surplus))
+      .accounts({ authority: admin, protocolConfig, tradingPool, tradingPoolVault })
+      .rpc();
+
+    const funded = await program.account.tradingPool.fetch(tradingPool);
+    expect(funded.totalPoolAmount.toNumber()).to.equal(surplus);
+
+    try {
+      await program.methods
+        .withdrawPoolSurplus(new anchor.BN(surplus + 1))
+        .accounts({ authority: admin, protocolConfig, tradingPool, tradingPoolVault, lpMint })
+        .rpc();
+
+      expect.fail("Withdrawing more than free liquidity should have failed");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("InsufficientPoolLiquidity");
+    }
+
+    // Leaves the pool empty for the first LP deposit
+    await program.methods
+      .withdrawPoolSurplus(new anchor.BN(surplus))
+      .accounts({ authority: admin, protocolConfig, tradingPool, tradingPoolVault, lpMint })
+      .rpc();
+
+    const withdrawn = await program.account.tradingPool.fetch(tradingPool);
+    expect(withdrawn.totalPoolAmount.toNumber()).to.equal(0);
+    expect(await provider.connection.getBalance(tradingPoolVault)).to.equal(rent);
+  });
+
+  it("LP deposit mints shares at NAV", async () => {
+    await program.methods
+      .lpDeposit(new anchor.BN(depositAmount))
+      .accounts({
+        user: lp.publicKey,
+        tradingPool,
+        tradingPoolVault,
+        lpMint,
+        userLpToken: lpToken,
+        poolLpToken,
+        protocolConfig,
+      })
+      .signers([lp])
+      .rpc();
+
+    // Empty pool prices shares 1:1, less the minimum locked in the pool
+    const shares = await provider.connection.getTokenAccountBalance(lpToken);
+    expect(shares.value.amount).to.equal((depositAmount - minLockedShares).toString());
+
+    const locked = await provider.connection.getTokenAccountBalance(poolLpToken);
+    expect(locked.value.amount).to.equal(minLockedShares.toString());
+
+    const pool = await program.account.tradingPool.fetch(tradingPool);
+    expect(pool.totalPoolAmount.toNumber()).to.equal(depositAmount);
+  });
+
+  it("Reports pool stats via return data", async () => {
+    const stats = await program.methods
+      .getPoolStats()
+      .accounts({ tradingPool })
+      .view();
+
+    // No open positions, so all pool funds are free
+    expect(stats.nav.toNumber()).to.equal(depositAmount);
+    expect(stats.freeLiquidity.toNumber()).to.equal(depositAmount);
+    expect(stats.utilizationBps.toNumber()).to.equal(0);
+  });
+
+  it("LP withdraw burns shares for lamports", async () => {
+    const vaultBalanceBefore = await provider.connection.getBalance(tradingPoolVault);
+
+    await program.methods
+      .lpWithdraw(new anchor.BN(depositAmount / 2))
+      .accounts({
+        user: lp.publicKey,
+        tradingPool,
+        tradingPoolVault,
+        lpMint,
+        userLpToken: lpToken,
+      })
+      .signers([lp])
+      .rpc();
+
+    const vaultBalanceAfter = await provider.connection.getBalance(tradingPoolVault);
+    expect(vaultBalanceBefore - vaultBalanceAfter).to.equal(depositAmount / 2);
+
+    const shares = await provider.connection.getTokenAccountBalance(lpToken);
+    expect(shares.value.amount).to.equal((depositAmount / 2 - minLockedShares).toString());
+  });
+
+  it("LP withdraw of more shares than held fails", async () => {
+    try {
+      await program.methods
+        .lpWithdraw(new anchor.BN(depositAmount))
+        .accounts({
+          user: lp.publicKey,
+          tradingPool,
+          tradingPoolVault,
+          lpMint,
+          userLpToken: lpToken,
+        })
+        .signers([lp])
+        .rpc();
+
+      expect.fail("Withdrawing more shares than held should have failed");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("InsufficientFunds");
+    }
+  });
+
+  it("Pool surplus withdrawal is closed once LP shares exist", async () => {
+    try {
+      await program.methods
+        .withdrawPoolSurplus(new anchor.BN(1))
+        .accounts({ authority: admin, protocolConfig, tradingPool, tradingPoolVault, lpMint })
+        .rpc();
+
+      expect.fail("Surplus withdrawal should be rejected while LP shares are outstanding");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("LpSharesOutstanding");
+    }
+  });
+
+  it("Initializes an empty position tree for compressed positions", async () => {
+    const treeId = new anchor.BN(0);
+    const [positionTree] = PublicKey.findProgramAddressSync(
+      [Buffer.from("position_tree"), tradingPool.toBuffer(), treeId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .initPositionTree(treeId)
+      .accounts({ authority: admin, tradingPool })
+      .rpc();
+
+    // Root of a depth 14 tree of empty leaves
+    let emptyRoot = Buffer.alloc(32);
+    for (let level = 0; level < 14; level++) {
+      emptyRoot = createHash("sha256").update(emptyRoot).update(emptyRoot).digest();
+    }
+
+    const tree = await program.account.positionTree.fetch(positionTree);
+    expect(tree.version).to.equal(1);
+    expect(tree.tradingPool.toBase58()).to.equal(tradingPool.toBase58());
+    expect(tree.nextLeafIndex.toNumber()).to.equal(0);
+    expect(Buffer.from(tree.changeLogs[tree.activeIndex.toNumber()].root)).to.deep.equal(emptyRoot);
+  });
+});
@@ -15,7 +15,8 @@ describe("Vault Position Tests", () => {
   const amount = LAMPORTS_PER_SOL * 0.1; // 0.1 SOL
   const lowerBound = 60000; // $60,000
   const upperBound = 70000; // $70,000
-  const orderId = 12345;
+  // Position ids are assigned by the program from the vault's counter
+  const firstPositionId = 0;
   const backendOrderId = 67890;
   
   // Test accounts
@@ -60,7 +61,7 @@ describe("Vault Position Tests", () => {
       [
         Buffer.from("position"),
         user.publicKey.toBuffer(),
-        new anchor.BN(orderId).toArrayLike(Buffer, "le", 8)
+        new anchor.BN(firstPositionId).toArrayLike(Buffer, "le", 8)
       ],
       program.programId
     );
@@ -72,7 +73,7 @@ describe("Vault Position Tests", () => {
       [
         Buffer.from("position"),
         user.publicKey.toBuffer(),
-        new anchor.BN(backendOrderId).toArrayLike(Buffer, "le", 8)
+        new anchor.BN(firstPositionId).toArrayLike(Buffer, "le", 8)
       ],
       program.programId
     );
@@ -105,6 +106,7 @@ describe("Vault Position Tests", () => {
       assert.equal(vaultStateAccount.authority.toString(), user.publicKey.toString());
       assert.equal(vaultStateAccount.stateBump, vaultStateBump);
       assert.equal(vaultStateAccount.vaultBump, vaultBump);
+      assert.equal(vaultStateAccount.nextPositionId.toNumber(), firstPositionId);
       
       console.log("Vault initialized successfully");
     } catch (e) {
@@ -166,8 +168,8 @@ describe("Vault Position Tests", () => {
           { breakout: {} }, // Use different position type for variety
           new anchor.BN(lowerBound),
           new anchor.BN(upperBound),
-          new anchor.BN(backendOrderId),
-          new anchor.BN(amount)
+          new anchor.BN(amount),
+          false
         )
         .accounts({
           user: user.publicKey, // User account (not a signer)
@@ -184,11 +186,12 @@ describe("Vault Position Tests", () => {
       // Verify position was created
       const positionAccount = await program.account.positionState.fetch(backendCreatedPosition);
       assert.equal(positionAccount.user.toString(), user.publicKey.toString());
-      assert.deepEqual(positionAccount.positionType, { breakout: {} });
+      // Zero-copy positions store enums as their discriminant: Breakout = 1
+      assert.equal(positionAccount.positionType, 1);
       assert.equal(positionAccount.lowerBound.toString(), lowerBound.toString());
       assert.equal(positionAccount.upperBound.toString(), backendOrderId.toString());
       assert.equal(positionAccount.amount.toString(), amount.toString());
-      assert.equal(positionAccount.status.active !== undefined, true);
+      assert.equal(positionAccount.status, 0);
       assert.equal(positionAccount.bump, backendPositionBump);
       
       // The admin should only pay for rent, not for the position amount
@@ -0,0 +1,104 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  checkPosition,
+  ensureKeeper,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  indexPageAddress,
+  openPosition,
+  pda,
+  priceOutOfBand,
+  protocolAccounts,
+} from "./helpers";
+
+describe("position index", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const { protocolConfig, treasury, tradingPool, tradingPoolVault } = protocolAccounts(program);
+  const amount = LAMPORTS_PER_SOL / 2;
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+  let indexPage: PublicKey;
+  const positions: PublicKey[] = [];
+
+  function claimPosition(position: PublicKey, positionIndex: PublicKey) {
+    const user = owner.user.publicKey;
+
+    return program.methods
+      .claimPosition()
+      .accounts({
+        user,
+        position,
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        tradingPool,
+        tradingPoolVault,
+        protocolConfig,
+        treasury,
+        userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+        positionIndex,
+      })
+      .signers([owner.user]);
+  }
+
+  async function listedIds() {
+    const page = await program.account.positionIndex.fetch(indexPage);
+    return page.positionIds.map((id) => id.toNumber());
+  }
+
+  before(async () => {
+    await ensureKeeper(program, provider.wallet.publicKey);
+    await ensurePositionMarket(program);
+    owner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    indexPage = indexPageAddress(program, owner.user.publicKey, 0);
+  });
+
+  it("Lists each new position id on the owner's index page", async () => {
+    for (let index = 0; index < 2; index++) {
+      const { position } = await openPosition(program, owner, { breakout: {} }, amount);
+      positions.push(position);
+    }
+
+    const page = await program.account.positionIndex.fetch(indexPage);
+    expect(page.user.toBase58()).to.equal(owner.user.publicKey.toBase58());
+    expect(page.page.toNumber()).to.equal(0);
+    expect(await listedIds()).to.deep.equal([0, 1]);
+  });
+
+  it("Rejects another user's index page on claim", async () => {
+    const other = await fundedUser(program, LAMPORTS_PER_SOL);
+    await openPosition(program, other, { breakout: {} }, amount);
+    await checkPosition(program, owner.user.publicKey, positions[0], priceOutOfBand);
+
+    await expectError(
+      claimPosition(positions[0], indexPageAddress(program, other.user.publicKey, 0)).rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("Removes a claimed position id from its page", async () => {
+    await claimPosition(positions[0], indexPage).rpc();
+
+    expect(await listedIds()).to.deep.equal([1]);
+  });
+
+  it("Closes the page once its last position is claimed, refunding the owner", async () => {
+    await checkPosition(program, owner.user.publicKey, positions[1], priceOutOfBand);
+    const pageRent = await provider.connection.getBalance(indexPage);
+    const ownerBefore = await provider.connection.getBalance(owner.user.publicKey);
+
+    await claimPosition(positions[1], indexPage).rpc();
+
+    expect(await provider.connection.getAccountInfo(indexPage)).to.be.null;
+    // The owner signs and pays the 5000 lamport fee out of the refunded rent
+    const ownerAfter = await provider.connection.getBalance(owner.user.publicKey);
+    expect(ownerAfter - ownerBefore).to.equal(pageRent - 5000);
+  });
+});
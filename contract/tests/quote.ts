@@ -0,0 +1,245 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import {
+  Ed25519Program,
+  Keypair,
+  PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  TransactionInstruction,
+  LAMPORTS_PER_SOL,
+} from "@solana/web3.js";
+import { expect } from "chai";
+import { ensureOrderBook, expectError, fundedUser, pda, protocolAccounts, u64Seed } from "./helpers";
+
+// Layout of an ed25519 program instruction: a count and padding byte, one 14 byte
+// offsets entry per signature, then the public keys, signatures and messages
+const headerSize = 2;
+const offsetsSize = 14;
+const currentInstruction = 0xffff;
+
+// Signature by `signer` over `message`, taken from the instruction web3.js builds
+function sign(signer: Keypair, message: Buffer): Buffer {
+  const ix = Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message });
+  const signatureOffset = ix.data.readUInt16LE(headerSize);
+  return ix.data.subarray(signatureOffset, signatureOffset + 64);
+}
+
+// An ed25519 instruction over any number of signatures, with every offset pointing
+// at `instructionIndex`, which is this instruction unless it is set otherwise
+function ed25519Instruction(
+  entries: { signer: Keypair; message: Buffer }[],
+  instructionIndex = currentInstruction
+): TransactionInstruction {
+  const header = Buffer.alloc(headerSize + entries.length * offsetsSize);
+  header.writeUInt8(entries.length, 0);
+
+  const body: Buffer[] = [];
+  let offset = header.length;
+  entries.forEach(({ signer, message }, index) => {
+    const publicKeyOffset = offset;
+    const signatureOffset = publicKeyOffset + 32;
+    const messageOffset = signatureOffset + 64;
+    body.push(signer.publicKey.toBuffer(), sign(signer, message), message);
+    offset = messageOffset + message.length;
+
+    const entry = headerSize + index * offsetsSize;
+    header.writeUInt16LE(signatureOffset, entry);
+    header.writeUInt16LE(instructionIndex, entry + 2);
+    header.writeUInt16LE(publicKeyOffset, entry + 4);
+    header.writeUInt16LE(instructionIndex, entry + 6);
+    header.writeUInt16LE(messageOffset, entry + 8);
+    header.writeUInt16LE(message.length, entry + 10);
+    header.writeUInt16LE(instructionIndex, entry + 12);
+  });
+
+  return new TransactionInstruction({
+    programId: Ed25519Program.programId,
+    keys: [],
+    data: Buffer.concat([header, ...body]),
+  });
+}
+
+describe("signed quotes", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const { protocolConfig, roles } = protocolAccounts(program);
+  const size = new anchor.BN(LAMPORTS_PER_SOL / 2);
+
+  let orderBook: PublicKey;
+  let maker: Awaited<ReturnType<typeof fundedUser>>;
+  let taker: Awaited<ReturnType<typeof fundedUser>>;
+  let nextNonce = 0;
+
+  // StayIn for the taker at 1.5x, so the maker puts up half the taker's stake
+  function quoteFrom(quoteMaker: PublicKey, overrides = {}) {
+    return {
+      maker: quoteMaker,
+      orderBook,
+      positionType: { stayIn: {} },
+      lowerBound: new anchor.BN(60000),
+      upperBound: new anchor.BN(70000),
+      oddsBps: 15000,
+      size,
+      expiry: new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+      nonce: new anchor.BN(nextNonce++),
+      ...overrides,
+    };
+  }
+
+  function encode(quote): Buffer {
+    return program.coder.types.encode("quote", quote);
+  }
+
+  async function fillQuote(quote, signatureIx: TransactionInstruction, quoteMaker = maker) {
+    const { nextMatchId } = await program.account.orderBook.fetch(orderBook);
+    const pairedPosition = pda(program, Buffer.from("paired_position"), orderBook.toBuffer(), u64Seed(nextMatchId));
+
+    return program.methods
+      .createPositionWithQuote(quote)
+      .accounts({
+        user: taker.user.publicKey,
+        maker: quoteMaker.user.publicKey,
+        orderBook,
+        pairedPosition,
+        pairedEscrow: pda(program, Buffer.from("paired_escrow"), pairedPosition.toBuffer()),
+        usedQuote: pda(program, Buffer.from("used_quote"), quote.maker.toBuffer(), u64Seed(quote.nonce)),
+        userVault: taker.vault,
+        userVaultState: taker.vaultState,
+        makerVault: quoteMaker.vault,
+        makerVaultState: quoteMaker.vaultState,
+        protocolConfig,
+        roles,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .preInstructions([signatureIx])
+      .signers([taker.user])
+      .rpc();
+  }
+
+  before(async () => {
+    ({ orderBook } = await ensureOrderBook(program));
+    maker = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    taker = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+
+    await program.methods
+      .grantRole({ marketMaker: {} }, maker.user.publicKey)
+      .accounts({ authority: provider.wallet.publicKey, protocolConfig, roles })
+      .rpc();
+  });
+
+  let filled;
+
+  it("Fills a quote signed by a market maker, each side staking from its vault", async () => {
+    filled = quoteFrom(maker.user.publicKey);
+    const { nextMatchId } = await program.account.orderBook.fetch(orderBook);
+    const makerBefore = await provider.connection.getBalance(maker.vault);
+    const takerBefore = await provider.connection.getBalance(taker.vault);
+
+    await fillQuote(filled, ed25519Instruction([{ signer: maker.user, message: encode(filled) }]));
+
+    const pairedPosition = pda(program, Buffer.from("paired_position"), orderBook.toBuffer(), u64Seed(nextMatchId));
+    const paired = await program.account.pairedPosition.fetch(pairedPosition);
+    expect(paired.stayInUser.toBase58()).to.equal(taker.user.publicKey.toBase58());
+    expect(paired.breakoutUser.toBase58()).to.equal(maker.user.publicKey.toBase58());
+    expect(paired.stayInStake.toNumber()).to.equal(size.toNumber());
+    expect(paired.breakoutStake.toNumber()).to.equal(size.toNumber() / 2);
+
+    expect(makerBefore - (await provider.connection.getBalance(maker.vault))).to.equal(size.toNumber() / 2);
+    expect(takerBefore - (await provider.connection.getBalance(taker.vault))).to.equal(size.toNumber());
+
+    const usedQuote = await program.account.usedQuote.fetch(
+      pda(program, Buffer.from("used_quote"), maker.user.publicKey.toBuffer(), u64Seed(filled.nonce))
+    );
+    expect(usedQuote.nonce.toNumber()).to.equal(filled.nonce.toNumber());
+  });
+
+  it("Rejects a quote whose nonce was already filled", async () => {
+    try {
+      await fillQuote(filled, ed25519Instruction([{ signer: maker.user, message: encode(filled) }]));
+      expect.fail("A used nonce should not fill again");
+    } catch (error) {
+      // The used quote account already exists, so creating it fails in the system program
+      expect(error.logs.join("\n")).to.include("already in use");
+    }
+  });
+
+  it("Rejects a quote signed by a key other than the maker's", async () => {
+    const quote = quoteFrom(maker.user.publicKey);
+
+    await expectError(
+      fillQuote(quote, ed25519Instruction([{ signer: Keypair.generate(), message: encode(quote) }])),
+      "InvalidQuoteSignature"
+    );
+  });
+
+  it("Rejects a quote whose bounds differ from the signed message", async () => {
+    const signed = quoteFrom(maker.user.publicKey);
+    const tampered = { ...signed, upperBound: new anchor.BN(90000) };
+
+    await expectError(
+      fillQuote(tampered, ed25519Instruction([{ signer: maker.user, message: encode(signed) }])),
+      "InvalidQuoteSignature"
+    );
+  });
+
+  it("Rejects a quote whose size differs from the signed message", async () => {
+    const signed = quoteFrom(maker.user.publicKey);
+    const tampered = { ...signed, size: size.muln(2) };
+
+    await expectError(
+      fillQuote(tampered, ed25519Instruction([{ signer: maker.user, message: encode(signed) }])),
+      "InvalidQuoteSignature"
+    );
+  });
+
+  it("Rejects a signature instruction carrying more than one signature", async () => {
+    const quote = quoteFrom(maker.user.publicKey);
+    const message = encode(quote);
+
+    await expectError(
+      fillQuote(
+        quote,
+        ed25519Instruction([
+          { signer: maker.user, message },
+          { signer: maker.user, message },
+        ])
+      ),
+      "InvalidQuoteSignature"
+    );
+  });
+
+  it("Rejects a signature whose data is read from another instruction index", async () => {
+    const quote = quoteFrom(maker.user.publicKey);
+
+    // Index 0 is the signature instruction itself, so the ed25519 program accepts it,
+    // but the program only trusts data inside that instruction through u16::MAX
+    await expectError(
+      fillQuote(quote, ed25519Instruction([{ signer: maker.user, message: encode(quote) }], 0)),
+      "InvalidQuoteSignature"
+    );
+  });
+
+  it("Rejects an expired quote", async () => {
+    const quote = quoteFrom(maker.user.publicKey, {
+      expiry: new anchor.BN(Math.floor(Date.now() / 1000) - 60),
+    });
+
+    await expectError(
+      fillQuote(quote, ed25519Instruction([{ signer: maker.user, message: encode(quote) }])),
+      "QuoteExpired"
+    );
+  });
+
+  it("Rejects a quote from a maker without the market maker role", async () => {
+    const outsider = await fundedUser(program, LAMPORTS_PER_SOL);
+    const quote = quoteFrom(outsider.user.publicKey);
+
+    await expectError(
+      fillQuote(quote, ed25519Instruction([{ signer: outsider.user, message: encode(quote) }]), outsider),
+      "MakerNotWhitelisted"
+    );
+  });
+});
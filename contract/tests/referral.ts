@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  openPosition,
+  pda,
+  protocolAccounts,
+} from "./helpers";
+
+describe("referrals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const { protocolConfig, treasury } = protocolAccounts(program);
+  const amount = new anchor.BN(LAMPORTS_PER_SOL);
+
+  // Set by pool.ts: 0.3% opening fee, 20% of it rebated to the referrer
+  const openingFee = amount.muln(30).divn(10000);
+  const rebate = openingFee.muln(2000).divn(10000);
+
+  let referrerOwner: Awaited<ReturnType<typeof fundedUser>>;
+  let trader: Awaited<ReturnType<typeof fundedUser>>;
+  let referrer: PublicKey;
+
+  function claimRewards() {
+    return program.methods
+      .claimReferralRewards()
+      .accounts({ authority: referrerOwner.user.publicKey, referrer, protocolConfig, treasury })
+      .signers([referrerOwner.user])
+      .rpc();
+  }
+
+  before(async () => {
+    await ensurePositionMarket(program);
+    // The referrer also trades, to check it cannot refer itself
+    referrerOwner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    trader = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    referrer = pda(program, Buffer.from("referrer"), referrerOwner.user.publicKey.toBuffer());
+  });
+
+  it("Registers a referrer with nothing accrued", async () => {
+    await program.methods
+      .registerReferrer()
+      .accounts({ authority: referrerOwner.user.publicKey, referrer })
+      .signers([referrerOwner.user])
+      .rpc();
+
+    const registered = await program.account.referrer.fetch(referrer);
+    expect(registered.authority.toBase58()).to.equal(referrerOwner.user.publicKey.toBase58());
+    expect(registered.referredPositions.toNumber()).to.equal(0);
+    expect(registered.claimable.toNumber()).to.equal(0);
+  });
+
+  it("Rejects a claim with nothing accrued", async () => {
+    await expectError(claimRewards(), "NoReferralRewards");
+  });
+
+  it("Accrues a share of each referred position's opening fee", async () => {
+    const configBefore = await program.account.protocolConfig.fetch(protocolConfig);
+
+    await openPosition(program, trader, { breakout: {} }, amount.toNumber(), 60000, 70000, referrer);
+    await openPosition(program, trader, { stayIn: {} }, amount.toNumber(), 60000, 70000, referrer);
+
+    const accrued = await program.account.referrer.fetch(referrer);
+    expect(accrued.referredPositions.toNumber()).to.equal(2);
+    expect(accrued.totalVolume.toString()).to.equal(amount.muln(2).toString());
+    expect(accrued.totalEarnings.toString()).to.equal(rebate.muln(2).toString());
+    expect(accrued.claimable.toString()).to.equal(rebate.muln(2).toString());
+
+    const configAfter = await program.account.protocolConfig.fetch(protocolConfig);
+    expect(configAfter.totalReferralOwed.sub(configBefore.totalReferralOwed).toString()).to.equal(
+      rebate.muln(2).toString()
+    );
+  });
+
+  it("Rejects a position referred by its own opener", async () => {
+    await expectError(
+      openPosition(program, referrerOwner, { breakout: {} }, amount.toNumber(), 60000, 70000, referrer),
+      "SelfReferral"
+    );
+  });
+
+  it("Pays the accrued rebates out of the treasury", async () => {
+    const configBefore = await program.account.protocolConfig.fetch(protocolConfig);
+    const treasuryBefore = await provider.connection.getBalance(treasury);
+    const ownerBefore = await provider.connection.getBalance(referrerOwner.user.publicKey);
+
+    await claimRewards();
+
+    const claimed = rebate.muln(2).toNumber();
+    expect(treasuryBefore - (await provider.connection.getBalance(treasury))).to.equal(claimed);
+    // The referrer signs and pays the 5000 lamport fee
+    expect((await provider.connection.getBalance(referrerOwner.user.publicKey)) - ownerBefore).to.equal(
+      claimed - 5000
+    );
+
+    const after = await program.account.referrer.fetch(referrer);
+    expect(after.claimable.toNumber()).to.equal(0);
+    expect(after.totalEarnings.toNumber()).to.equal(claimed);
+
+    const configAfter = await program.account.protocolConfig.fetch(protocolConfig);
+    expect(configBefore.totalReferralOwed.sub(configAfter.totalReferralOwed).toNumber()).to.equal(claimed);
+  });
+});
@@ -0,0 +1,154 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  checkPosition,
+  ensureKeeper,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  indexPageAddress,
+  openPosition,
+  outOfBandPrice,
+  pda,
+  positionAddress,
+  priceInBand,
+  priceOutOfBand,
+  protocolAccounts,
+} from "./helpers";
+
+describe("auto-rolling positions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const keeper = provider.wallet.publicKey;
+  const { protocolConfig, roles, treasury, tradingPool, tradingPoolVault } = protocolAccounts(program);
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+  let exposureBook: PublicKey;
+  let position: PublicKey;
+
+  function rollPosition(priceUpdate: PublicKey, nextPositionId = 1) {
+    const user = owner.user.publicKey;
+
+    // Every id in this suite falls on index page 0, which the rolled and the next id share,
+    // so the next id's page is left out
+    return program.methods
+      .rollPosition()
+      .accounts({
+        keeper,
+        user,
+        position,
+        nextPosition: positionAddress(program, user, nextPositionId),
+        userVault: owner.vault,
+        userVaultState: owner.vaultState,
+        tradingPool,
+        tradingPoolVault,
+        exposureBook,
+        protocolConfig,
+        treasury,
+        userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+        positionIndex: indexPageAddress(program, user, 0),
+        nextPositionIndex: null,
+        roles,
+        priceUpdate,
+      })
+      .rpc();
+  }
+
+  before(async () => {
+    await ensureKeeper(program, keeper);
+    exposureBook = await ensurePositionMarket(program);
+    owner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+    ({ position } = await openPosition(program, owner, { breakout: {} }, LAMPORTS_PER_SOL / 2));
+  });
+
+  it("Rejects a roll config without a band", async () => {
+    await expectError(
+      program.methods
+        .setRollConfig({ rollsRemaining: 2, bandWidthBps: 0, reinvest: { stake: {} } })
+        .accounts({ user: owner.user.publicKey, position })
+        .signers([owner.user])
+        .rpc(),
+      "InvalidRollConfig"
+    );
+  });
+
+  it("Sets the roll config on an open position", async () => {
+    await program.methods
+      .setRollConfig({ rollsRemaining: 2, bandWidthBps: 500, reinvest: { stake: {} } })
+      .accounts({ user: owner.user.publicKey, position })
+      .signers([owner.user])
+      .rpc();
+
+    const configured = await program.account.positionState.fetch(position);
+    expect(configured.hasRollConfig).to.equal(1);
+    expect(configured.rollsRemaining).to.equal(2);
+    expect(configured.rollBandWidthBps).to.equal(500);
+  });
+
+  it("Rejects rolling a position that is still running in its band", async () => {
+    await expectError(rollPosition(priceInBand), "PositionNotDue");
+  });
+
+  it("Settles, claims and reopens the position around the current price", async () => {
+    const keeperBefore = await provider.connection.getBalance(keeper);
+    const rolledStake = (await program.account.positionState.fetch(position)).amount;
+
+    await rollPosition(priceOutOfBand);
+
+    // Claimed = 2
+    const rolled = await program.account.positionState.fetch(position);
+    expect(rolled.status).to.equal(2);
+    expect(rolled.settlementPrice.toNumber()).to.equal(outOfBandPrice);
+
+    // Reinvests the old stake on the same side, in a +/- 5% band around the price
+    const next = await program.account.positionState.fetch(positionAddress(program, owner.user.publicKey, 1));
+    expect(next.positionId.toNumber()).to.equal(1);
+    expect(next.positionType).to.equal(1);
+    expect(next.status).to.equal(0);
+    expect(next.lowerBound.toNumber()).to.equal(outOfBandPrice * 0.95);
+    expect(next.upperBound.toNumber()).to.equal(outOfBandPrice * 1.05);
+    expect(next.amount.toNumber()).to.be.lessThan(rolledStake.toNumber());
+    expect(next.hasRollConfig).to.equal(1);
+    expect(next.rollsRemaining).to.equal(1);
+
+    // The owner's vault pays the new position's rent, the keeper only the transaction fee
+    const keeperAfter = await provider.connection.getBalance(keeper);
+    expect(keeperBefore - keeperAfter).to.equal(5000);
+
+    const index = await program.account.positionIndex.fetch(indexPageAddress(program, owner.user.publicKey, 1));
+    expect(index.positionIds.map((id) => id.toNumber())).to.deep.equal([1]);
+  });
+
+  it("Rolls a position that check_position settled first", async () => {
+    const rolledFrom = position;
+    position = positionAddress(program, owner.user.publicKey, 1);
+    // The price is outside the band recentered on the previous roll
+    await checkPosition(program, owner.user.publicKey, position, priceInBand);
+    const settled = await program.account.positionState.fetch(position);
+    expect(settled.status).to.equal(1);
+
+    await rollPosition(priceInBand, 2);
+
+    const rolled = await program.account.positionState.fetch(position);
+    expect(rolled.status).to.equal(2);
+    // Rolled on its recorded outcome, not resettled at the roll price
+    expect(rolled.settlementPrice.toNumber()).to.equal(settled.settlementPrice.toNumber());
+    expect(rolled.payoutPercentage).to.equal(settled.payoutPercentage);
+
+    // That was the last roll, so the next position carries no config
+    const next = await program.account.positionState.fetch(positionAddress(program, owner.user.publicKey, 2));
+    expect(next.status).to.equal(0);
+    expect(next.hasRollConfig).to.equal(0);
+
+    position = rolledFrom;
+  });
+
+  it("Rejects rolling a claimed position", async () => {
+    await expectError(rollPosition(priceInBand, 3), "AlreadyClaimed");
+  });
+});
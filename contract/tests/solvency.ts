@@ -0,0 +1,79 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  ensureKeeper,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  openPosition,
+  protocolAccounts,
+} from "./helpers";
+
+describe("pool solvency", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const admin = provider.wallet.publicKey;
+  const { protocolConfig, roles, tradingPool } = protocolAccounts(program);
+  const amount = LAMPORTS_PER_SOL / 2;
+  // Set by pool.ts, restored after each test that changes it
+  const utilizationCap = 9000;
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+
+  function setUtilizationCap(maxUtilizationBps: number) {
+    return program.methods
+      .updatePoolConfig(maxUtilizationBps)
+      .accounts({ riskManager: admin, tradingPool, protocolConfig, roles })
+      .rpc();
+  }
+
+  before(async () => {
+    await ensureKeeper(program, admin);
+    await ensurePositionMarket(program);
+    owner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+  });
+
+  afterEach(async () => {
+    await setUtilizationCap(utilizationCap);
+  });
+
+  it("Rejects a position whose max payout would take the pool over its utilization cap", async () => {
+    // At 1 bps the cap allows 0.01 SOL per 100 SOL pooled, less than any position's max payout
+    await setUtilizationCap(1);
+    const poolBefore = await program.account.tradingPool.fetch(tradingPool);
+
+    await expectError(openPosition(program, owner, { breakout: {} }, amount), "UtilizationCapExceeded");
+
+    const poolAfter = await program.account.tradingPool.fetch(tradingPool);
+    expect(poolAfter.worstCaseLiability.toString()).to.equal(poolBefore.worstCaseLiability.toString());
+    expect(poolAfter.totalPoolAmount.toString()).to.equal(poolBefore.totalPoolAmount.toString());
+  });
+
+  it("Reserves the max payout of an accepted position against the pool", async () => {
+    const poolBefore = await program.account.tradingPool.fetch(tradingPool);
+
+    const { position } = await openPosition(program, owner, { breakout: {} }, amount);
+
+    // The stake joins the pool, its worst case payout of 2x is reserved
+    const opened = await program.account.positionState.fetch(position);
+    const poolAfter = await program.account.tradingPool.fetch(tradingPool);
+    expect(poolAfter.totalPoolAmount.sub(poolBefore.totalPoolAmount).toString()).to.equal(opened.amount.toString());
+    expect(poolAfter.worstCaseLiability.sub(poolBefore.worstCaseLiability).toString()).to.equal(
+      opened.amount.muln(2).toString()
+    );
+    expect(poolAfter.expectedLiability.sub(poolBefore.expectedLiability).toString()).to.equal(
+      opened.amount.toString()
+    );
+    expect(poolAfter.worstCaseLiability.muln(10000).lte(poolAfter.totalPoolAmount.muln(utilizationCap))).to.be.true;
+  });
+
+  it("Rejects a utilization cap of zero or above 100%", async () => {
+    await expectError(setUtilizationCap(0), "InvalidPoolConfig");
+    await expectError(setUtilizationCap(10001), "InvalidPoolConfig");
+  });
+});
@@ -0,0 +1,208 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  checkPosition,
+  ensureKeeper,
+  ensurePositionMarket,
+  expectError,
+  fundedUser,
+  indexPageAddress,
+  openPosition,
+  pda,
+  positionAddress,
+  priceInBand,
+  priceOutOfBand,
+  protocolAccounts,
+} from "./helpers";
+
+describe("tokenized positions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const { protocolConfig, treasury, tradingPool, tradingPoolVault } = protocolAccounts(program);
+  const amount = LAMPORTS_PER_SOL / 2;
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+  let exposureBook: PublicKey;
+
+  // Tokenized at creation, settled in the owner's favour
+  let position: PublicKey;
+  let positionMint: PublicKey;
+  let ownerPositionToken: PublicKey;
+
+  function createAccounts(positionId: number) {
+    const user = owner.user.publicKey;
+    const newPosition = positionAddress(program, user, positionId);
+    const mint = pda(program, Buffer.from("position_mint"), newPosition.toBuffer());
+
+    return {
+      user,
+      position: newPosition,
+      userVault: owner.vault,
+      userVaultState: owner.vaultState,
+      tradingPool,
+      tradingPoolVault,
+      protocolConfig,
+      treasury,
+      userStats: pda(program, Buffer.from("user_stats"), user.toBuffer()),
+      positionIndex: indexPageAddress(program, user, positionId),
+      referrer: null,
+      exposureBook,
+      priceUpdate: priceInBand,
+      positionMint: mint,
+      userPositionToken: anchor.utils.token.associatedAddress({ mint, owner: user }),
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    };
+  }
+
+  function claimAccounts() {
+    return {
+      holder: owner.user.publicKey,
+      position,
+      positionMint,
+      holderPositionToken: ownerPositionToken,
+      holderVault: owner.vault,
+      holderVaultState: owner.vaultState,
+      ownerVaultState: owner.vaultState,
+      tradingPool,
+      tradingPoolVault,
+      protocolConfig,
+      treasury,
+      positionIndex: indexPageAddress(program, owner.user.publicKey, 0),
+      owner: owner.user.publicKey,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+    };
+  }
+
+  before(async () => {
+    await ensureKeeper(program, provider.wallet.publicKey);
+    exposureBook = await ensurePositionMarket(program);
+    owner = await fundedUser(program, 3 * LAMPORTS_PER_SOL);
+  });
+
+  it("Rejects tokenizing at creation without the token accounts", async () => {
+    await expectError(
+      program.methods
+        .createPosition({ breakout: {} }, new anchor.BN(60000), new anchor.BN(70000), new anchor.BN(amount), true)
+        .accounts({
+          ...createAccounts(0),
+          positionMint: null,
+          userPositionToken: null,
+          tokenProgram: null,
+          associatedTokenProgram: null,
+        })
+        .signers([owner.user])
+        .rpc(),
+      "MissingPositionTokenAccounts"
+    );
+  });
+
+  it("Rejects token accounts on a position that is not tokenized", async () => {
+    await expectError(
+      program.methods
+        .createPosition({ breakout: {} }, new anchor.BN(60000), new anchor.BN(70000), new anchor.BN(amount), false)
+        .accounts(createAccounts(0))
+        .signers([owner.user])
+        .rpc(),
+      "UnexpectedPositionTokenAccounts"
+    );
+  });
+
+  it("Mints the position token to the opener at creation", async () => {
+    const accounts = createAccounts(0);
+    position = accounts.position;
+    positionMint = accounts.positionMint;
+    ownerPositionToken = accounts.userPositionToken;
+
+    await program.methods
+      .createPosition({ breakout: {} }, new anchor.BN(60000), new anchor.BN(70000), new anchor.BN(amount), true)
+      .accounts(accounts)
+      .signers([owner.user])
+      .rpc();
+
+    const created = await program.account.positionState.fetch(position);
+    expect(created.positionMint.toBase58()).to.equal(positionMint.toBase58());
+
+    const balance = await provider.connection.getTokenAccountBalance(ownerPositionToken);
+    expect(balance.value.amount).to.equal("1");
+  });
+
+  it("Rejects a claim before the position settles", async () => {
+    await expectError(
+      program.methods.claimTokenizedPosition().accounts(claimAccounts()).signers([owner.user]).rpc(),
+      "PositionNotSettled"
+    );
+  });
+
+  it("Rejects claiming a tokenized position without the token", async () => {
+    await checkPosition(program, owner.user.publicKey, position, priceOutOfBand);
+
+    await expectError(
+      program.methods
+        .claimPosition()
+        .accounts({
+          user: owner.user.publicKey,
+          position,
+          userVault: owner.vault,
+          userVaultState: owner.vaultState,
+          tradingPool,
+          tradingPoolVault,
+          protocolConfig,
+          treasury,
+          userStats: pda(program, Buffer.from("user_stats"), owner.user.publicKey.toBuffer()),
+          positionIndex: indexPageAddress(program, owner.user.publicKey, 0),
+        })
+        .signers([owner.user])
+        .rpc(),
+      "PositionTokenized"
+    );
+  });
+
+  it("Pays the token holder and burns the token on claim", async () => {
+    const vaultBefore = await provider.connection.getBalance(owner.vault);
+
+    await program.methods.claimTokenizedPosition().accounts(claimAccounts()).signers([owner.user]).rpc();
+
+    const claimed = await program.account.positionState.fetch(position);
+    // Claimed = 2
+    expect(claimed.status).to.equal(2);
+
+    const vaultAfter = await provider.connection.getBalance(owner.vault);
+    expect(vaultAfter).to.be.greaterThan(vaultBefore);
+
+    const balance = await provider.connection.getTokenAccountBalance(ownerPositionToken);
+    expect(balance.value.amount).to.equal("0");
+
+    // The settled position was the only id on its index page, which is closed with it
+    const indexPage = await provider.connection.getAccountInfo(indexPageAddress(program, owner.user.publicKey, 0));
+    expect(indexPage).to.be.null;
+  });
+
+  it("Tokenizes an open position once", async () => {
+    const { position: untokenized } = await openPosition(program, owner, { stayIn: {} }, amount);
+    const mint = pda(program, Buffer.from("position_mint"), untokenized.toBuffer());
+    const accounts = {
+      user: owner.user.publicKey,
+      position: untokenized,
+      positionMint: mint,
+      userPositionToken: anchor.utils.token.associatedAddress({ mint, owner: owner.user.publicKey }),
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    };
+
+    await program.methods.tokenizePosition().accounts(accounts).signers([owner.user]).rpc();
+
+    const tokenized = await program.account.positionState.fetch(untokenized);
+    expect(tokenized.positionMint.toBase58()).to.equal(mint.toBase58());
+
+    await expectError(
+      program.methods.tokenizePosition().accounts(accounts).signers([owner.user]).rpc(),
+      "PositionAlreadyTokenized"
+    );
+  });
+});
@@ -0,0 +1,92 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  checkPosition,
+  claimPosition,
+  ensureKeeper,
+  ensurePositionMarket,
+  fundedUser,
+  openPosition,
+  pda,
+  priceOutOfBand,
+  protocolAccounts,
+} from "./helpers";
+
+describe("user stats", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const admin = provider.wallet.publicKey;
+  const { treasury } = protocolAccounts(program);
+  const amount = new anchor.BN(LAMPORTS_PER_SOL);
+
+  // Set by pool.ts: 0.3% opening fee
+  const openingFee = amount.muln(30).divn(10000);
+  const stake = amount.sub(openingFee);
+
+  let owner: Awaited<ReturnType<typeof fundedUser>>;
+  let userStats: PublicKey;
+  let stayIn: PublicKey;
+  let breakout: PublicKey;
+
+  before(async () => {
+    await ensureKeeper(program, admin);
+    await ensurePositionMarket(program);
+    owner = await fundedUser(program, 5 * LAMPORTS_PER_SOL);
+    userStats = pda(program, Buffer.from("user_stats"), owner.user.publicKey.toBuffer());
+  });
+
+  it("Counts opened positions, their stake and the opening fees", async () => {
+    ({ position: stayIn } = await openPosition(program, owner, { stayIn: {} }, amount.toNumber()));
+    ({ position: breakout } = await openPosition(program, owner, { breakout: {} }, amount.toNumber()));
+
+    const stats = await program.account.userStats.fetch(userStats);
+    expect(stats.user.toString()).to.equal(owner.user.publicKey.toString());
+    expect(stats.positionsOpened.toNumber()).to.equal(2);
+    expect(stats.openPositions.toNumber()).to.equal(2);
+    expect(stats.openStake.toString()).to.equal(stake.muln(2).toString());
+    expect(stats.totalStaked.toString()).to.equal(stake.muln(2).toString());
+    expect(stats.totalFeesPaid.toString()).to.equal(openingFee.muln(2).toString());
+    expect(stats.realizedPnl.toString()).to.equal(openingFee.muln(2).neg().toString());
+  });
+
+  it("Records each side's outcome at settlement", async () => {
+    await checkPosition(program, owner.user.publicKey, stayIn, priceOutOfBand);
+    await checkPosition(program, owner.user.publicKey, breakout, priceOutOfBand);
+
+    const stats = await program.account.userStats.fetch(userStats);
+    expect(stats.openPositions.toNumber()).to.equal(0);
+    expect(stats.openStake.toNumber()).to.equal(0);
+    expect(stats.stayInWins.toNumber()).to.equal(0);
+    expect(stats.stayInLosses.toNumber()).to.equal(1);
+    expect(stats.breakoutWins.toNumber()).to.equal(1);
+    expect(stats.breakoutLosses.toNumber()).to.equal(0);
+
+    // Settlement leaves the totals alone
+    expect(stats.positionsOpened.toNumber()).to.equal(2);
+    expect(stats.totalStaked.toString()).to.equal(stake.muln(2).toString());
+  });
+
+  it("Adds payouts, winnings fees and realized PnL at claim", async () => {
+    const vaultBefore = await provider.connection.getBalance(owner.vault);
+    const treasuryBefore = await provider.connection.getBalance(treasury);
+
+    await claimPosition(program, owner, stayIn);
+    await claimPosition(program, owner, breakout);
+
+    const paidOut = (await provider.connection.getBalance(owner.vault)) - vaultBefore;
+    const winningsFees = (await provider.connection.getBalance(treasury)) - treasuryBefore;
+    expect(winningsFees).to.be.greaterThan(0);
+
+    const stats = await program.account.userStats.fetch(userStats);
+    expect(stats.totalPaidOut.toNumber()).to.equal(paidOut);
+    expect(stats.totalFeesPaid.toNumber()).to.equal(openingFee.muln(2).toNumber() + winningsFees);
+    expect(stats.realizedPnl.toNumber()).to.equal(
+      paidOut - stake.muln(2).toNumber() - openingFee.muln(2).toNumber()
+    );
+  });
+});